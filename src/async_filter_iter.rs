@@ -0,0 +1,182 @@
+use fancy_regex::Regex;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::{
+    filter_iter::{
+        is_ignored, is_included, LineDecoder, RecordJoiner, Region, RegionState, Stride,
+    },
+    prefilter::Prefilter,
+};
+
+/// Asynchronous counterpart of [`FilterIter`](crate::filter_iter::FilterIter), reading from a
+/// `tokio::io::AsyncRead`, see [`crate::filter_iter_async`].
+///
+/// Lines which cannot be read are yielded as errors, reading continues with the next line.
+pub struct AsyncFilterIter<Reader> {
+    reader: BufReader<Reader>,
+    decoder: LineDecoder,
+    skip: usize,
+    stride: Stride,
+    region: Region,
+    includes: Prefilter,
+    excludes: Prefilter,
+    records: Option<RecordJoiner>,
+    lines_read: usize,
+    line_number: usize,
+}
+
+impl<Reader> AsyncFilterIter<Reader>
+where
+    Reader: AsyncRead + Unpin,
+{
+    pub fn new(reader: Reader, included_lines: Vec<Regex>, excluded_lines: Vec<Regex>) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            decoder: LineDecoder::default(),
+            skip: 0,
+            stride: Stride::default(),
+            region: Region::default(),
+            includes: Prefilter::new(&included_lines),
+            excludes: Prefilter::new(&excluded_lines),
+            records: None,
+            lines_read: 0,
+            line_number: 0,
+        }
+    }
+
+    /// Joins lines into records, each starting with a line matching `record_start`.
+    pub fn with_record_start(mut self, record_start: Option<Regex>) -> Self {
+        self.records = record_start.map(RecordJoiner::new);
+        self
+    }
+
+    /// Replaces invalid UTF-8 sequences by `U+FFFD` instead of failing to read the line.
+    pub fn with_lossy_utf8(mut self, lossy: bool) -> Self {
+        self.decoder.lossy = lossy;
+        self
+    }
+
+    /// Skips the first `lines` lines of the input, before joining records and filtering.
+    pub fn with_skip_lines(mut self, lines: usize) -> Self {
+        self.skip = lines;
+        self
+    }
+
+    /// Only passes the records after the first one matching `start` and stops at the next one matching `stop`.
+    pub fn with_region(mut self, start: Option<Regex>, stop: Option<Regex>) -> Self {
+        self.region = Region::new(start, stop);
+        self
+    }
+
+    /// Only keeps every `stride`th record passing the filters.
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = Stride::new(stride);
+        self
+    }
+
+    /// Returns the 1-based number of the line last returned, the first line for records,
+    /// or of the line which could not be read.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Returns the number of lines read so far, including the ones filtered out.
+    pub fn lines_read(&self) -> usize {
+        self.lines_read
+    }
+
+    /// Returns the number of lines read so far which contained invalid UTF-8, see
+    /// [`AsyncFilterIter::with_lossy_utf8`].
+    pub fn lossy_lines(&self) -> usize {
+        self.decoder.lossy_lines
+    }
+
+    async fn next_record(&mut self) -> std::io::Result<Option<String>> {
+        while self.skip > 0 {
+            self.skip -= 1;
+            if self.reader.read_until(b'\n', &mut Vec::new()).await? == 0 {
+                return Ok(None);
+            }
+            self.lines_read += 1;
+        }
+        loop {
+            let line = read_line(&mut self.reader, &mut self.decoder).await;
+            if matches!(line, Ok(Some(_)) | Err(_)) {
+                self.lines_read += 1;
+            }
+            let Some(records) = &mut self.records else {
+                self.line_number = self.lines_read;
+                return line;
+            };
+            match line {
+                Ok(Some(line)) => {
+                    if let Some((number, record)) = records.push(line, self.lines_read) {
+                        self.line_number = number;
+                        return Ok(Some(record));
+                    }
+                }
+                Err(e) => {
+                    self.line_number = self.lines_read;
+                    return Err(e);
+                }
+                Ok(None) => {
+                    return Ok(records.finish().map(|(number, record)| {
+                        self.line_number = number;
+                        record
+                    }))
+                }
+            }
+        }
+    }
+
+    /// Returns the next record passing the filters, or `None` at the end of the input.
+    pub async fn next(&mut self) -> Option<std::io::Result<String>> {
+        self.next_with(|_| {}).await
+    }
+
+    /// Returns the next record passing the filters, calling `on_record` for every record read,
+    /// including the ones which are filtered out.
+    pub async fn next_with(
+        &mut self,
+        mut on_record: impl FnMut(&str),
+    ) -> Option<std::io::Result<String>> {
+        loop {
+            match self.next_record().await {
+                Ok(Some(line)) => {
+                    on_record(&line);
+                    match self.region.check(&line) {
+                        RegionState::Before => continue,
+                        RegionState::Inside => {}
+                        RegionState::After => return None,
+                    }
+                    if !is_included(&line, &self.includes) {
+                        continue;
+                    }
+                    if is_ignored(&line, &self.excludes) {
+                        continue;
+                    }
+                    if !self.stride.keep() {
+                        continue;
+                    }
+                    return Some(Ok(line));
+                }
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+async fn read_line<Reader>(
+    reader: &mut BufReader<Reader>,
+    decoder: &mut LineDecoder,
+) -> std::io::Result<Option<String>>
+where
+    Reader: AsyncRead + Unpin,
+{
+    let mut line = Vec::new();
+    match reader.read_until(b'\n', &mut line).await? {
+        0 => Ok(None),
+        _ => decoder.decode(line).map(Some),
+    }
+}
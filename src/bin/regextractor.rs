@@ -0,0 +1,1230 @@
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use fancy_regex::Regex;
+use regextractor::{
+    chain::ChainReader,
+    config::ExtractionConfig,
+    datatable::{csv::CsvOptions, json::JsonLayout, markdown::MarkdownOptions, DataTable},
+    decompress,
+    diagnostics::ExpressionKind,
+    extractor::Extractor,
+    flags::RegexFlags,
+    follow::FollowReader,
+    logfmt,
+    numeric::Numeric,
+    NamedRegex, Row,
+};
+use std::{
+    error::Error,
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    ops::ControlFlow,
+    path::PathBuf,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+type CliResult<T = ()> = Result<T, Box<dyn Error>>;
+
+// Exit codes besides success, like grep's. The commands return the number of rows or lines they found,
+// none of them is reported by `NO_MATCHES`. Rows without any extracted value don't count, see `matched_rows`. A data expression failing `--require-matches` is a `USAGE_ERROR`.
+const NO_MATCHES: u8 = 1;
+const USAGE_ERROR: u8 = 2;
+const IO_ERROR: u8 = 3;
+
+#[derive(Parser, Debug)]
+#[command(
+    after_help = "Exit status: 0 if data or lines were found, 1 if nothing matched, 2 for invalid options or expressions and for data expressions failing --require-matches, 3 for IO errors"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+/// Simple program extract data from line based text files like logs or gcode
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Extracts data into a csv format
+    ExtractData(ExtractArgs),
+    /// Filter input based on regular expressions
+    FilterData(FilterArgs),
+    /// Print the count, missing values, minimum, maximum, mean and standard deviation of every extracted column
+    Stats(StatsArgs),
+    /// Extracts data and draws the columns against the base column as line chart into an SVG or PNG file
+    #[cfg(feature = "plot")]
+    Plot(PlotArgs),
+    /// Follow a file like 'tail -f' and print the data extracted from new lines as they are written
+    Watch(WatchArgs),
+}
+
+/// Flags applied to all expressions
+#[derive(Args, Debug)]
+struct FlagArgs {
+    /// Match all expressions case-insensitively
+    #[arg(long)]
+    ignore_case: bool,
+
+    /// Let '.' in all expressions also match line breaks, e.g. within multiline records
+    #[arg(long)]
+    dot_all: bool,
+}
+
+impl FlagArgs {
+    fn regex_flags(&self) -> RegexFlags {
+        RegexFlags {
+            case_insensitive: self.ignore_case,
+            dot_matches_newline: self.dot_all,
+        }
+    }
+}
+
+/// Where the output is written to
+#[derive(Args, Debug)]
+struct OutputArgs {
+    /// Write the output into this file instead of stdout and report the number of rows written on stderr
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Append to the output file instead of overwriting it
+    #[arg(long, requires = "output")]
+    append: bool,
+}
+
+impl OutputArgs {
+    /// Returns whether the output is appended to a file which already has content.
+    fn appends_to_content(&self) -> bool {
+        self.append
+            && self
+                .output
+                .as_ref()
+                .and_then(|path| fs::metadata(path).ok())
+                .is_some_and(|meta| meta.len() > 0)
+    }
+
+    fn writer(&self) -> CliResult<Box<dyn Write>> {
+        let Some(path) = &self.output else {
+            return Ok(Box::new(io::stdout().lock()));
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(self.append)
+            .truncate(!self.append)
+            .open(path)
+            .map_err(|e| io_error("open", path.display(), e))?;
+        Ok(Box::new(BufWriter::new(file)))
+    }
+
+    /// Reports the number of rows written into the output file.
+    fn report(&self, rows: usize) {
+        if let Some(path) = &self.output {
+            eprintln!("{} rows written to '{}'", rows, path.display());
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct FilterArgs {
+    /// Input file or glob pattern like 'logs/*.log', compressed files are decompressed. Can be specified
+    /// several times to filter the files one after another, like rotated logs. Reads stdin if omitted or '-'
+    #[arg(short, long)]
+    file: Vec<String>,
+
+    /// Data is only extracted from lines which match one of these expressions. Can be specified several times. All lines are included if no regex is specified
+    #[arg(short, long)]
+    include_expr: Vec<String>,
+
+    /// Data is not extracted from lines which match one of these expressions. Can be specified several times
+    #[arg(short, long)]
+    skip_expr: Vec<String>,
+
+    /// Print the lines matching none of the include expressions instead
+    #[arg(short = 'v', long)]
+    invert: bool,
+
+    /// Also print this many lines after each matching line
+    #[arg(short = 'A', long, default_value_t = 0)]
+    after_context: usize,
+
+    /// Also print this many lines before each matching line
+    #[arg(short = 'B', long, default_value_t = 0)]
+    before_context: usize,
+
+    /// Also print this many lines before and after each matching line
+    #[arg(short = 'C', long)]
+    context: Option<usize>,
+
+    /// Only print the matches of the include expressions, or this group of them, one per line
+    #[arg(short, long, num_args = 0..=1, default_missing_value = "0")]
+    only_matching: Option<usize>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Replace matches in the printed lines, given as '<expr>=<replacement>'. The replacement may refer to
+    /// groups like '$1'. Can be specified several times
+    #[arg(short, long)]
+    replace: Vec<String>,
+
+    /// Input and output lines are separated by zero bytes instead of line breaks, e.g. for 'find -print0'
+    #[arg(short = 'z', long)]
+    null_data: bool,
+
+    /// Print consecutive equal lines only once
+    #[arg(long)]
+    dedup: bool,
+
+    /// Ignore the parts of the lines matching this expression, like a timestamp, when comparing them for '--dedup'
+    #[arg(long, requires = "dedup")]
+    dedup_ignore: Option<String>,
+
+    /// Stop after printing this many lines
+    #[arg(short = 'm', long)]
+    max_lines: Option<usize>,
+
+    /// Only print this many lines from the end of the output
+    #[arg(long)]
+    last_lines: Option<usize>,
+
+    /// Report on stderr how many lines were read and printed and how long it took
+    #[arg(long, action = ArgAction::Count)]
+    verbose: u8,
+
+    #[command(flatten)]
+    flags: FlagArgs,
+}
+
+#[derive(Args, Debug)]
+struct ExtractArgs {
+    /// Input file or glob pattern like 'logs/*.log', compressed files are decompressed. Can be specified
+    /// several times to extract data from all files into one table with an additional 'source' column.
+    /// Reads stdin if omitted or '-'
+    #[arg(short, long)]
+    file: Vec<String>,
+
+    #[command(flatten)]
+    extraction: ExtractionArgs,
+
+    /// Only check the expressions: report all invalid ones and count how many of the first lines each one matches,
+    /// without extracting data
+    #[arg(long)]
+    check: bool,
+
+    /// Number of lines scanned by '--check'
+    #[arg(long, default_value_t = 1000, requires = "check")]
+    check_lines: usize,
+
+    #[command(flatten)]
+    table: TableArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Args, Debug)]
+struct StatsArgs {
+    /// Input file or glob pattern like 'logs/*.log', compressed files are decompressed. Can be specified
+    /// several times to compute the statistics over all files. Reads stdin if omitted or '-'
+    #[arg(short, long)]
+    file: Vec<String>,
+
+    #[command(flatten)]
+    extraction: ExtractionArgs,
+
+    /// Number of decimal places of the statistics, all significant ones by default
+    #[arg(long)]
+    precision: Option<usize>,
+}
+
+#[cfg(feature = "plot")]
+#[derive(Args, Debug)]
+struct PlotArgs {
+    /// Input file or glob pattern like 'logs/*.log', compressed files are decompressed. Can be specified
+    /// several times to plot the data of all files. Reads stdin if omitted or '-'
+    #[arg(short, long)]
+    file: Vec<String>,
+
+    #[command(flatten)]
+    extraction: ExtractionArgs,
+
+    /// The SVG or PNG file the chart is written to, chosen by the extension
+    #[arg(long)]
+    output: PathBuf,
+
+    /// Column to plot. Can be specified several times, all columns except the base column are plotted by default
+    #[arg(short, long)]
+    column: Vec<String>,
+
+    /// Title above the chart
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Label of the horizontal axis, the name of the base column by default
+    #[arg(long)]
+    x_label: Option<String>,
+
+    /// Label of the vertical axis
+    #[arg(long)]
+    y_label: Option<String>,
+
+    /// Width of the chart in pixels
+    #[arg(long, default_value_t = 800)]
+    width: u32,
+
+    /// Height of the chart in pixels
+    #[arg(long, default_value_t = 600)]
+    height: u32,
+}
+
+#[derive(Args, Debug)]
+struct WatchArgs {
+    /// File to follow, or '-' for stdin. Compressed files cannot be followed
+    #[arg(short, long)]
+    file: String,
+
+    /// Print the lines passing the include and skip expressions instead of extracting data
+    #[arg(long)]
+    lines: bool,
+
+    /// Also process the lines already in the file instead of only the new ones
+    #[arg(long)]
+    from_start: bool,
+
+    /// Milliseconds to wait before looking for new lines again
+    #[arg(long, default_value_t = 250)]
+    interval: u64,
+
+    #[command(flatten)]
+    extraction: ExtractionArgs,
+
+    #[command(flatten)]
+    table: TableArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// How data is extracted from the lines
+#[derive(Args, Debug)]
+struct ExtractionArgs {
+    /// Extraction profile (JSON, TOML or YAML, chosen by the extension) with data expressions, filters and formats. The other options extend it
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Regex to extract data from a line. Can be specified several times to extract multiple values from a line.
+    /// An expression with several named groups populates one column per group.
+    #[arg(short, long)]
+    data_expr: Vec<String>,
+
+    /// Data expression given with its name, like 'temp=T:(\d+\.\d+)', instead of '--data-expr' and '--names'.
+    /// Split at the first '='. Can be specified several times, the columns follow those of '--data-expr'
+    #[arg(long = "expr", value_name = "NAME=REGEX", value_parser = parse_named_expr)]
+    named_expr: Vec<(String, String)>,
+
+    /// Name of the extracted data. Has to be the same order as 'data_expr'.
+    #[arg(short, long)]
+    names: Vec<String>,
+
+    /// Name of the column used as base data, like a timestamp. It becomes the first column of the output
+    #[arg(long)]
+    base: Option<String>,
+
+    /// Regex to extract text like a log level from a line into a text column. Can be specified several times
+    #[arg(short, long)]
+    text_expr: Vec<String>,
+
+    /// Data is only extracted from lines which match one of these expressions. Can be specified several times
+    #[arg(short, long)]
+    include_expr: Vec<String>,
+
+    /// Data is not extracted from lines which match one of these expressions. Can be specified several times
+    #[arg(short, long)]
+    skip_expr: Vec<String>,
+
+    /// Use the first group of the match as data instead of the full match.
+    /// Expressions containing a named group always use that group.
+    #[arg(short, long)]
+    group: bool,
+
+    /// Extract the values of all key=value pairs (logfmt), one column per key found in the files
+    #[arg(long)]
+    logfmt: bool,
+
+    /// Data is only extracted after the first line matching this expression
+    #[arg(long)]
+    start_expr: Option<String>,
+
+    /// Data extraction stops at the first line after the start matching this expression
+    #[arg(long)]
+    stop_expr: Option<String>,
+
+    /// Number of lines at the start of each file to ignore, like banners or headers
+    #[arg(long, default_value_t = 0)]
+    skip_lines: usize,
+
+    /// Only extract data from every n-th line passing the include and skip expressions
+    #[arg(long, default_value_t = 1)]
+    sample_every: usize,
+
+    /// Fail if a data expression does not match any line
+    #[arg(long)]
+    require_matches: bool,
+
+    /// Extract the values as 32 bit floats, which keep only about 7 significant digits,
+    /// too few for timestamps or large counters
+    #[arg(long)]
+    f32: bool,
+
+    /// Report on stderr how many lines were read, filtered out and matched by each data expression and how long
+    /// it took. Given twice, it also reports every value which could not be parsed
+    #[arg(short, long, action = ArgAction::Count)]
+    verbose: u8,
+
+    #[command(flatten)]
+    flags: FlagArgs,
+}
+
+/// How the extracted table is written
+#[derive(Args, Debug)]
+struct TableArgs {
+    /// Format of the extracted table
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    format: OutputFormat,
+
+    /// Separates the values in csv and tsv output, overriding the default of the format
+    #[arg(long)]
+    delimiter: Option<char>,
+
+    /// Number of decimal places of the values in csv, tsv and markdown output, all significant ones by default
+    #[arg(long)]
+    precision: Option<usize>,
+
+    /// Written instead of missing values in csv and tsv output
+    #[arg(long, default_value = "NaN")]
+    null_value: String,
+}
+
+impl TableArgs {
+    /// Returns the options for csv and tsv output.
+    fn csv_options(&self, header: bool) -> CsvOptions {
+        let default_delimiter = if self.format == OutputFormat::Tsv {
+            '\t'
+        } else {
+            ';'
+        };
+        CsvOptions {
+            delimiter: self.delimiter.unwrap_or(default_delimiter),
+            precision: self.precision,
+            nan_placeholder: self.null_value.clone(),
+            header,
+            ..CsvOptions::default()
+        }
+    }
+}
+
+/// Formats the extracted table can be written in
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Values separated by ';'
+    Csv,
+    /// Values separated by tabs
+    Tsv,
+    /// An array with one object per row
+    Json,
+    /// A markdown table
+    Markdown,
+}
+
+fn main() -> ExitCode {
+    let args = Cli::parse();
+
+    let result = match args.command {
+        Commands::ExtractData(args) if args.extraction.f32 => extract::<f32>(args),
+        Commands::ExtractData(args) => extract::<f64>(args),
+        Commands::FilterData(args) => filter(args),
+        Commands::Stats(args) if args.extraction.f32 => stats::<f32>(args),
+        Commands::Stats(args) => stats::<f64>(args),
+        #[cfg(feature = "plot")]
+        Commands::Plot(args) if args.extraction.f32 => plot::<f32>(args),
+        #[cfg(feature = "plot")]
+        Commands::Plot(args) => plot::<f64>(args),
+        Commands::Watch(args) if args.extraction.f32 => watch::<f32>(args),
+        Commands::Watch(args) => watch::<f64>(args),
+    };
+    match result {
+        Ok(0) => ExitCode::from(NO_MATCHES),
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) if is_broken_pipe(&*e) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            let mut source = e.source();
+            while let Some(e) = source {
+                eprintln!("  caused by: {}", e);
+                source = e.source();
+            }
+            ExitCode::from(exit_code(&*e))
+        }
+    }
+}
+
+/// Returns the exit code for `e`: IO errors anywhere in its chain, data expressions which did not match
+/// with '--require-matches', or anything else like invalid expressions or options.
+fn exit_code(e: &(dyn Error + 'static)) -> u8 {
+    let mut source = Some(e);
+    while let Some(e) = source {
+        if e.is::<io::Error>() {
+            return IO_ERROR;
+        }
+        source = e.source();
+    }
+    USAGE_ERROR
+}
+
+/// Returns whether `e` is caused by the reader of the output going away, like `head`.
+fn is_broken_pipe(e: &(dyn Error + 'static)) -> bool {
+    e.downcast_ref::<io::Error>()
+        .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+}
+
+/// Adds the file `path` to the IO error `e`, keeping its kind.
+fn io_error(action: &str, path: impl Display, e: io::Error) -> io::Error {
+    io::Error::new(e.kind(), format!("could not {} '{}': {}", action, path, e))
+}
+
+/// The file name standing for stdin.
+const STDIN: &str = "-";
+
+/// Opens the file at `path`, or stdin for `-`, decompressing it on the fly if it is compressed.
+fn open(path: &str) -> CliResult<Box<dyn Read + Send>> {
+    let reader = if path == STDIN {
+        decompress::decompress(io::stdin())
+    } else {
+        decompress::open(path)
+    };
+    reader.map_err(|e| io_error("open", path, e).into())
+}
+
+/// Returns `files` with glob patterns like `logs/*.log` expanded in alphabetical order, or stdin if there are none.
+fn files_or_stdin(files: Vec<String>) -> CliResult<Vec<String>> {
+    if files.is_empty() {
+        return Ok(vec![STDIN.to_string()]);
+    }
+    let mut expanded = vec![];
+    for file in files {
+        if file == STDIN || !file.contains(['*', '?', '[']) {
+            expanded.push(file);
+            continue;
+        }
+        let paths = glob::glob(&file)
+            .map_err(|e| format!("invalid file pattern '{}': {}", file, e))?
+            .map(|path| {
+                let path = path.map_err(io::Error::from)?;
+                Ok(path.to_string_lossy().into_owned())
+            })
+            .collect::<CliResult<Vec<_>>>()?;
+        if paths.is_empty() {
+            let message = format!("no file matches '{}'", file);
+            return Err(io::Error::new(io::ErrorKind::NotFound, message).into());
+        }
+        expanded.extend(paths);
+    }
+    Ok(expanded)
+}
+
+/// Pairs the data expressions with their names given by `names` in the same order,
+/// followed by the expressions given with their name by `--expr`.
+fn named_data_exprs<'a>(
+    exprs: &'a [String],
+    names: &'a [String],
+    named: &'a [(String, String)],
+) -> Vec<(Option<&'a str>, &'a str)> {
+    let names = names
+        .iter()
+        .map(|name| Some(name.as_str()))
+        .chain(std::iter::repeat(None));
+    exprs
+        .iter()
+        .zip(names)
+        .map(|(expr, name)| (name, expr.as_str()))
+        .chain(
+            named
+                .iter()
+                .map(|(name, expr)| (Some(name.as_str()), expr.as_str())),
+        )
+        .collect()
+}
+
+/// Parses the argument of `--expr` like `temp=T:(\d+)` into its name and regex.
+fn parse_named_expr(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, regex)) if !name.is_empty() && !regex.is_empty() => {
+            Ok((name.to_string(), regex.to_string()))
+        }
+        _ => Err("expected NAME=REGEX, like 'temp=T:(\\d+)'".to_string()),
+    }
+}
+
+/// Logs the diagnostics of the extraction to stderr, more detailed for each `-v`.
+fn init_logger(verbose: u8) {
+    let level = match verbose {
+        0 => log::LevelFilter::Off,
+        1 => log::LevelFilter::Info,
+        2 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(level)
+        .format_timestamp(None)
+        .format_target(false)
+        .init();
+}
+
+/// Compiles `expr` with the flags given on the command line.
+fn compile(flags: RegexFlags, expr: &str) -> CliResult<Regex> {
+    flags
+        .compile(expr)
+        .map_err(|e| format!("invalid regular expression '{}': {}", expr, e).into())
+}
+
+/// Builds the extractor configured on the command line for `files`, returning it with the base column.
+fn build_extractor<T: Numeric>(
+    args: ExtractionArgs,
+    files: &[String],
+) -> CliResult<(Extractor<T>, Option<String>)> {
+    let ExtractionArgs {
+        config,
+        data_expr,
+        named_expr,
+        names,
+        base,
+        text_expr,
+        include_expr,
+        skip_expr,
+        group,
+        logfmt,
+        start_expr,
+        stop_expr,
+        skip_lines,
+        sample_every,
+        require_matches,
+        f32: _,
+        verbose,
+        flags,
+    } = args;
+    let flags = flags.regex_flags();
+    init_logger(verbose);
+
+    let profile = config
+        .map(ExtractionConfig::from_path)
+        .transpose()?
+        .unwrap_or_default();
+    // Like filter-data, unreadable lines are IO errors instead of being skipped.
+    let mut extractor = profile.extractor::<T>()?.fail_on_read_errors(true);
+    // Only options given on the command line override the profile.
+    if group {
+        extractor = extractor.group_mode(true);
+    }
+    if skip_lines > 0 {
+        extractor = extractor.skip_lines(skip_lines);
+    }
+    if sample_every != 1 {
+        extractor = extractor.sample_every(sample_every);
+    }
+    if require_matches {
+        extractor = extractor.require_matches(true);
+    }
+
+    let mut counter = 0;
+
+    for (expr_name, expr) in named_data_exprs(&data_expr, &names, &named_expr) {
+        let regex = match NamedRegex::new_multi_capture(expr, expr)
+            .filter(|r| expr_name.is_none() && r.column_names().len() > 1)
+            .or_else(|| NamedRegex::new_with_capture(expr_name, expr, None))
+        {
+            Some(regex) => regex,
+            None => {
+                let name = if let Some(n) = expr_name {
+                    n.to_string()
+                } else {
+                    counter += 1;
+                    counter.to_string()
+                };
+                NamedRegex::try_new(&name, expr)?
+            }
+        };
+
+        let regex = regex.with_flags(flags)?;
+        extractor = extractor.with_data_regex(regex);
+    }
+
+    for expr in text_expr {
+        let regex = match NamedRegex::new_with_capture(None, &expr, None) {
+            Some(regex) => regex,
+            None => {
+                counter += 1;
+                NamedRegex::try_new(&counter.to_string(), &expr)?
+            }
+        };
+
+        let regex = regex.with_flags(flags)?;
+        extractor = extractor.with_data_regex(regex.as_text());
+    }
+
+    if logfmt {
+        let mut keys = Vec::<String>::new();
+        for f in files {
+            if f == STDIN {
+                return Err("--logfmt reads the input twice and cannot be used with stdin".into());
+            }
+            let reader = open(f)?;
+            let file_keys = logfmt::keys(reader, None).map_err(|e| io_error("read", f, e))?;
+            for key in file_keys {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        extractor = extractor.with_data_regex(NamedRegex::new_logfmt(&keys));
+    }
+
+    for incl in include_expr {
+        extractor = extractor.include(compile(flags, &incl)?);
+    }
+    for ignr in skip_expr {
+        extractor = extractor.exclude(compile(flags, &ignr)?);
+    }
+
+    if let Some(start) = start_expr {
+        extractor = extractor.start_marker(compile(flags, &start)?);
+    }
+    if let Some(stop) = stop_expr {
+        extractor = extractor.stop_marker(compile(flags, &stop)?);
+    }
+
+    let base = base.or(profile.base_data);
+    if let Some(base) = &base {
+        let columns = extractor.column_names();
+        if !columns.contains(base) {
+            return Err(format!(
+                "the base column '{}' is none of the extracted columns: {}",
+                base,
+                columns.join(", ")
+            )
+            .into());
+        }
+        extractor = extractor.base_data(base);
+    }
+    Ok((extractor, base))
+}
+
+/// Moves the `base` column in front of the other columns of `data`.
+fn base_first<T: Numeric>(data: &mut DataTable<T>, base: &str) -> CliResult {
+    let columns: Vec<String> = std::iter::once(base)
+        .chain(
+            data.get_names()
+                .map(String::as_str)
+                .filter(|name| *name != base),
+        )
+        .chain(data.get_text_names().map(String::as_str))
+        .map(String::from)
+        .collect();
+    let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+    data.select_columns(&columns)?;
+    Ok(())
+}
+
+/// Returns the number of rows of `data` with at least one value or one of the texts extracted by `extractor`,
+/// as rows of lines without any match are kept with missing values.
+fn matched_rows<T: Numeric>(data: &DataTable<T>, extractor: &Extractor<T>) -> usize {
+    let texts = extractor.text_column_names();
+    data.iter()
+        .filter(|row| {
+            row.values().any(|value| !value.is_missing())
+                || texts
+                    .iter()
+                    .any(|name| row.get_text(name).is_some_and(|text| !text.is_empty()))
+        })
+        .count()
+}
+
+/// Extracts the data from `files`, adding a 'source' column if there are several.
+fn run<T: Numeric>(extractor: &Extractor<T>, files: Vec<String>) -> CliResult<DataTable<T>> {
+    let files = files
+        .into_iter()
+        .map(|f| {
+            let reader = open(&f)?;
+            Ok((f, reader))
+        })
+        .collect::<CliResult<Vec<_>>>()?;
+
+    let start = Instant::now();
+    let data = if files.len() > 1 {
+        extractor.run_multi(files, "source")?
+    } else {
+        let (_, reader) = files.into_iter().next().ok_or("one file is required")?;
+        extractor.run(reader)?
+    };
+    log::info!(
+        "extracted {} rows in {:.2?}",
+        data.iter().len(),
+        start.elapsed()
+    );
+    Ok(data)
+}
+
+fn extract<T: Numeric + Display>(args: ExtractArgs) -> CliResult<usize> {
+    let ExtractArgs {
+        file,
+        extraction,
+        check: check_only,
+        check_lines,
+        table,
+        output,
+    } = args;
+    let file = files_or_stdin(file)?;
+    if check_only {
+        return check::<T>(extraction, file, check_lines);
+    }
+    if output.append && table.format == OutputFormat::Json {
+        return Err(
+            "--append cannot be used with --format json, which writes a single document".into(),
+        );
+    }
+    let (extractor, base) = build_extractor::<T>(extraction, &file)?;
+    let mut data = run(&extractor, file)?;
+
+    if let Some(base) = &base {
+        base_first(&mut data, base)?;
+    }
+
+    let header = !output.appends_to_content();
+    let mut writer = output.writer()?;
+    match table.format {
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            data.write_csv(&mut writer, table.csv_options(header))?;
+        }
+        OutputFormat::Json => writeln!(writer, "{}", data.to_json(JsonLayout::Records))?,
+        OutputFormat::Markdown => {
+            let options = MarkdownOptions {
+                precision: table.precision,
+                ..MarkdownOptions::default()
+            };
+            write!(writer, "{}", data.to_markdown(options))?
+        }
+    }
+    writer.flush()?;
+    output.report(data.iter().len());
+    Ok(matched_rows(&data, &extractor))
+}
+
+fn filter(args: FilterArgs) -> CliResult<usize> {
+    let FilterArgs {
+        file,
+        include_expr,
+        skip_expr,
+        invert,
+        after_context,
+        before_context,
+        context,
+        max_lines,
+        null_data,
+        dedup,
+        dedup_ignore,
+        last_lines,
+        only_matching,
+        replace,
+        output,
+        verbose,
+        flags,
+    } = args;
+    let flags = flags.regex_flags();
+    init_logger(verbose);
+    let file = files_or_stdin(file)?;
+
+    let readers = file
+        .iter()
+        .map(|f| open(f))
+        .collect::<CliResult<Vec<_>>>()?;
+    let file = ChainReader::new(readers);
+
+    let includes = include_expr
+        .iter()
+        .map(|incl| compile(flags, incl))
+        .collect::<CliResult<_>>()?;
+    let ignores = skip_expr
+        .iter()
+        .map(|excl| compile(flags, excl))
+        .collect::<CliResult<_>>()?;
+
+    let mut writer = output.writer()?;
+    let mut lines = regextractor::filter_iter(file, includes, ignores)
+        .with_invert(invert)
+        .with_before_context(context.unwrap_or(before_context))
+        .with_after_context(context.unwrap_or(after_context));
+    let delimiter = if null_data { "\0" } else { "\n" };
+    lines = lines.with_delimiter(delimiter);
+    if dedup {
+        let ignore = dedup_ignore.map(|expr| compile(flags, &expr)).transpose()?;
+        lines = lines.with_dedup_consecutive(ignore);
+    }
+    if let Some(group) = only_matching {
+        lines = lines.with_only_matching(group);
+    }
+    for replacement in replace {
+        let (expr, replacement) = replacement.rsplit_once('=').ok_or_else(|| {
+            format!(
+                "invalid replacement '{}', expected '<expr>=<replacement>'",
+                replacement
+            )
+        })?;
+        lines = lines.with_replacement(compile(flags, expr)?, replacement);
+    }
+    if let Some(max_lines) = max_lines {
+        lines = lines.with_max_lines(max_lines);
+    }
+    if let Some(last_lines) = last_lines {
+        lines = lines.with_last_lines(last_lines);
+    }
+    let start = Instant::now();
+    let mut rows = 0;
+    while let Some(line) = lines.next() {
+        let line = line.map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("could not read line {}: {}", lines.line_number(), e),
+            )
+        })?;
+        write!(writer, "{}{}", line, delimiter)?;
+        rows += 1;
+    }
+    writer.flush()?;
+    log::info!(
+        "printed {} of {} lines in {:.2?}",
+        rows,
+        lines.lines_read(),
+        start.elapsed()
+    );
+    output.report(rows);
+    Ok(rows)
+}
+
+/// Reports the invalid expressions, or how many of the first `lines` lines of `files` each expression matches.
+fn check<T: Numeric>(
+    extraction: ExtractionArgs,
+    files: Vec<String>,
+    lines: usize,
+) -> CliResult<usize> {
+    let flags = extraction.flags.regex_flags();
+    let data = named_data_exprs(
+        &extraction.data_expr,
+        &extraction.names,
+        &extraction.named_expr,
+    )
+    .into_iter()
+    .map(|(name, expr)| ("data", name.unwrap_or(expr), expr));
+    let others = [
+        ("text", &extraction.text_expr),
+        ("include", &extraction.include_expr),
+        ("exclude", &extraction.skip_expr),
+    ]
+    .into_iter()
+    .flat_map(|(kind, exprs)| {
+        exprs
+            .iter()
+            .map(move |expr| (kind, expr.as_str(), expr.as_str()))
+    })
+    .chain(
+        [
+            ("start marker", &extraction.start_expr),
+            ("stop marker", &extraction.stop_expr),
+        ]
+        .into_iter()
+        .filter_map(|(kind, expr)| expr.as_deref().map(|expr| (kind, expr, expr))),
+    );
+    let mut invalid = 0;
+    for (kind, name, expr) in data.chain(others) {
+        if let Err(e) = flags.compile(expr) {
+            eprintln!(
+                "{} expression '{}': invalid regular expression '{}': {}",
+                kind, name, expr, e
+            );
+            invalid += 1;
+        }
+    }
+    if invalid > 0 {
+        return Err(format!("{} invalid expressions", invalid).into());
+    }
+
+    let (extractor, _) = build_extractor::<T>(extraction, &files)?;
+    let readers = files
+        .iter()
+        .map(|f| open(f))
+        .collect::<CliResult<Vec<_>>>()?;
+    let counts = extractor.count_matches(ChainReader::new(readers), lines)?;
+
+    let matches = counts
+        .expressions
+        .iter()
+        .filter(|e| matches!(e.kind, ExpressionKind::Data | ExpressionKind::Text))
+        .map(|e| e.lines)
+        .sum();
+    let rows: Vec<Vec<String>> = counts
+        .expressions
+        .into_iter()
+        .map(|e| vec![e.kind.to_string(), e.name, e.lines.to_string()])
+        .collect();
+    let mut writer = io::stdout().lock();
+    write_table(
+        &mut writer,
+        &["expression", "name", "matched lines"],
+        &rows,
+        2,
+    )?;
+    writeln!(
+        writer,
+        "{} of {} scanned lines pass the include and skip expressions",
+        counts.passed, counts.lines
+    )?;
+    writer.flush()?;
+    Ok(matches)
+}
+
+fn stats<T: Numeric>(args: StatsArgs) -> CliResult<usize> {
+    let StatsArgs {
+        file,
+        extraction,
+        precision,
+    } = args;
+    let file = files_or_stdin(file)?;
+    let (extractor, base) = build_extractor::<T>(extraction, &file)?;
+    let mut data = run(&extractor, file)?;
+    if let Some(base) = &base {
+        base_first(&mut data, base)?;
+    }
+
+    let number = |value: Option<f64>| match (value, precision) {
+        (Some(value), Some(precision)) => format!("{:.*}", precision, value),
+        (Some(value), None) => value.to_string(),
+        (None, _) => "-".to_string(),
+    };
+    let header = ["column", "count", "missing", "min", "max", "mean", "std"];
+    let rows: Vec<Vec<String>> = data
+        .describe()
+        .into_iter()
+        .map(|stats| {
+            vec![
+                stats.name,
+                stats.count.to_string(),
+                stats.missing.to_string(),
+                number(stats.min),
+                number(stats.max),
+                number(stats.mean),
+                number(stats.std),
+            ]
+        })
+        .collect();
+
+    let mut writer = io::stdout().lock();
+    write_table(&mut writer, &header, &rows, 1)?;
+    writer.flush()?;
+    Ok(matched_rows(&data, &extractor))
+}
+
+/// Writes an aligned table with the first `left_aligned` columns left-aligned and the others right-aligned.
+fn write_table(
+    writer: &mut impl Write,
+    header: &[&str],
+    rows: &[Vec<String>],
+    left_aligned: usize,
+) -> io::Result<()> {
+    let header: Vec<String> = header.iter().map(|name| name.to_string()).collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            std::iter::once(&header)
+                .chain(rows)
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let line = |writer: &mut dyn Write, row: &[String]| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                if column < left_aligned {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect();
+        writeln!(writer, "{}", cells.join(" | ").trim_end())
+    };
+
+    line(writer, &header)?;
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    writeln!(writer, "{}", separator.join("-+-"))?;
+    for row in rows {
+        line(writer, row)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "plot")]
+fn plot<T: Numeric>(args: PlotArgs) -> CliResult<usize> {
+    use regextractor::datatable::plot::PlotOptions;
+
+    let PlotArgs {
+        file,
+        extraction,
+        output,
+        column,
+        title,
+        x_label,
+        y_label,
+        width,
+        height,
+    } = args;
+    let png = match output.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("svg") => false,
+        Some(ext) if ext.eq_ignore_ascii_case("png") => true,
+        _ => {
+            return Err(format!(
+                "cannot write '{}', charts are written as SVG or PNG files only",
+                output.display()
+            )
+            .into())
+        }
+    };
+    let file = files_or_stdin(file)?;
+    let (extractor, base) = build_extractor::<T>(extraction, &file)?;
+    let data = run(&extractor, file)?;
+
+    let columns: Vec<&str> = if column.is_empty() {
+        data.get_names()
+            .map(String::as_str)
+            .filter(|name| Some(*name) != base.as_deref())
+            .collect()
+    } else {
+        column.iter().map(String::as_str).collect()
+    };
+    if let Some(unknown) = columns.iter().find(|c| data.get_col_by_name(c).is_err()) {
+        let names: Vec<&str> = data.get_names().map(String::as_str).collect();
+        return Err(format!(
+            "the column '{}' is none of the extracted columns: {}",
+            unknown,
+            names.join(", ")
+        )
+        .into());
+    }
+    let options = PlotOptions {
+        title,
+        x_label: x_label.or(base),
+        y_label,
+        width,
+        height,
+    };
+    let plotted = if png {
+        data.plot_png(&output, &columns, options)
+    } else {
+        data.plot_svg(&output, &columns, options)
+    };
+    plotted.map_err(|e| io_error("write", output.display(), e))?;
+    eprintln!(
+        "{} rows of {} plotted into '{}'",
+        data.iter().len(),
+        columns.join(", "),
+        output.display()
+    );
+    Ok(matched_rows(&data, &extractor))
+}
+
+/// Writes `row` as csv, the base column first, and flushes it right away.
+fn write_row<T: Numeric + Display>(
+    writer: &mut impl Write,
+    row: &Row<T>,
+    base: Option<&str>,
+    options: &CsvOptions,
+) -> io::Result<()> {
+    let base = base.and_then(|base| row.names().iter().position(|name| name == base));
+    let columns: Vec<usize> = base
+        .into_iter()
+        .chain((0..row.names().len()).filter(|i| Some(*i) != base))
+        .collect();
+    if options.header {
+        let names = columns.iter().map(|&i| &row.names()[i]);
+        options.write_header(writer, names.chain(row.text_names()))?;
+    }
+    let values = columns.iter().map(|&i| row.values()[i]);
+    options.write_row(writer, values, row.texts())?;
+    writer.flush()
+}
+
+fn watch<T: Numeric + Display>(args: WatchArgs) -> CliResult<usize> {
+    let WatchArgs {
+        file,
+        lines,
+        from_start,
+        interval,
+        extraction,
+        table,
+        output,
+    } = args;
+    if !matches!(table.format, OutputFormat::Csv | OutputFormat::Tsv) {
+        return Err("watch writes the rows as csv or tsv only".into());
+    }
+    let reader: Box<dyn Read + Send> = if file == STDIN {
+        Box::new(io::stdin())
+    } else {
+        let mut reader = File::open(&file).map_err(|e| io_error("open", &file, e))?;
+        if !from_start {
+            reader.seek(SeekFrom::End(0))?;
+        }
+        Box::new(reader)
+    };
+    let interval = Duration::from_millis(interval);
+    let mut writer = output.writer()?;
+
+    let (extractor, base) = build_extractor::<T>(extraction, std::slice::from_ref(&file))?;
+
+    if lines {
+        let mut lines = extractor.filter_lines(FollowReader::new(reader, interval));
+        let mut passed = 0;
+        while let Some(line) = lines.next() {
+            // Unreadable lines are reported, following continues with the next one.
+            match line {
+                Ok(line) => {
+                    writeln!(writer, "{}", line)?;
+                    writer.flush()?;
+                    passed += 1;
+                }
+                Err(e) => eprintln!("could not read line {}: {}", lines.line_number(), e),
+            }
+        }
+        return Ok(passed);
+    }
+
+    let mut header = !output.appends_to_content();
+    let mut result = Ok(());
+    let mut rows = 0;
+    extractor.follow(reader, interval, |row| {
+        result = write_row(
+            &mut writer,
+            &row,
+            base.as_deref(),
+            &table.csv_options(header),
+        );
+        header = false;
+        rows += 1;
+        if result.is_err() {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })?;
+    result?;
+    Ok(rows)
+}
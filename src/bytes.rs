@@ -0,0 +1,203 @@
+use std::io::{BufRead, BufReader, Read};
+
+use regex::bytes::Captures;
+pub use regex::bytes::Regex;
+
+use crate::{
+    datatable::{builder::DataTableBuilder, DataTable},
+    error::ExtractionError,
+    format::{parse_value, ValueFormat},
+    numeric::Numeric,
+    Capture,
+};
+
+/// A byte regex populating one or more numeric columns, see `NamedRegex`.
+#[derive(Debug, Clone)]
+pub struct NamedBytesRegex {
+    pub name: String,
+    pub regex: Regex,
+    pub capture: Capture,
+}
+
+impl NamedBytesRegex {
+    pub fn new_from_string(name: &str, regex: &str) -> Option<Self> {
+        Some(Self {
+            name: name.into(),
+            regex: Regex::new(regex).ok()?,
+            capture: Capture::Positional,
+        })
+    }
+
+    /// Uses the given capture, `Capture::Logfmt` is not supported.
+    pub fn with_capture(mut self, capture: Capture) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Returns the names of the columns populated by this regex.
+    pub fn column_names(&self) -> Vec<String> {
+        match self.capture {
+            Capture::AllNamed => self
+                .regex
+                .capture_names()
+                .flatten()
+                .map(str::to_string)
+                .collect(),
+            _ => vec![self.name.clone()],
+        }
+    }
+
+    /// Returns the value of each column for the first match in `line`.
+    fn get_values<T: Numeric>(&self, line: &[u8], group: bool) -> Vec<Option<T>> {
+        let captures = self.regex.captures(line);
+        let value = |select: &dyn Fn(&Captures) -> Option<T>| captures.as_ref().and_then(select);
+        let parse = |bytes: &[u8]| {
+            parse_value(std::str::from_utf8(bytes).ok()?, &ValueFormat::Number, None)
+        };
+        match &self.capture {
+            Capture::AllNamed => self
+                .regex
+                .capture_names()
+                .flatten()
+                .map(|n| value(&|c| parse(c.name(n)?.as_bytes())))
+                .collect(),
+            Capture::Named(n) => vec![value(&|c| parse(c.name(n)?.as_bytes()))],
+            _ => {
+                let index = if group { 1 } else { 0 };
+                vec![value(&|c| parse(c.get(index)?.as_bytes()))]
+            }
+        }
+    }
+}
+
+/// Byte oriented counterpart of [`crate::extract_data`] for inputs with stray non-UTF-8 bytes,
+/// like logs with embedded binary data.
+///
+/// Lines are filtered and matched as raw bytes with `regex::bytes`, only the captured values have to be
+/// valid UTF-8. Look-around and backreferences are not supported. Every line passing the filters produces a row. Values which are missing or not valid UTF-8 numbers are NaN,
+/// rows with such values are skipped for types without NaN like integers.
+///
+/// # Errors
+///
+/// Returns an `ExtractionError` if the data table cannot be constructed. Lines which cannot be read are skipped.
+pub fn extract_data_bytes<Reader, T>(
+    reader: Reader,
+    data_regex: Vec<NamedBytesRegex>,
+    included_lines: Vec<Regex>,
+    excluded_lines: Vec<Regex>,
+    base_data_name: Option<&str>,
+    group: bool,
+) -> Result<DataTable<T>, ExtractionError>
+where
+    Reader: Read,
+    T: Numeric,
+{
+    let names: Vec<_> = data_regex.iter().flat_map(|r| r.column_names()).collect();
+    let mut builder = DataTableBuilder::new(&names)?;
+
+    for line in ByteLines::new(reader, included_lines, excluded_lines).flatten() {
+        let values: Option<Vec<T>> = data_regex
+            .iter()
+            .flat_map(|r| r.get_values::<T>(&line, group))
+            .map(|v| v.or_else(T::missing))
+            .collect();
+        if let Some(values) = values {
+            builder.add_row(&values, &[])?;
+        }
+    }
+
+    Ok(builder.build(base_data_name)?)
+}
+
+/// Byte oriented counterpart of [`crate::filter`], returning the raw bytes of the lines without line ending.
+///
+/// # Errors
+///
+/// Lines which cannot be read are skipped, so this currently always succeeds.
+pub fn filter_bytes<Reader>(
+    reader: Reader,
+    included_lines: Vec<Regex>,
+    excluded_lines: Vec<Regex>,
+) -> Result<Vec<Vec<u8>>, ExtractionError>
+where
+    Reader: Read,
+{
+    Ok(ByteLines::new(reader, included_lines, excluded_lines)
+        .flatten()
+        .collect())
+}
+
+/// Iterates over the lines of a reader passing the include and exclude filters.
+struct ByteLines<Reader> {
+    reader: BufReader<Reader>,
+    includes: Vec<Regex>,
+    excludes: Vec<Regex>,
+}
+
+impl<Reader: Read> ByteLines<Reader> {
+    fn new(reader: Reader, includes: Vec<Regex>, excludes: Vec<Regex>) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            includes,
+            excludes,
+        }
+    }
+}
+
+impl<Reader: Read> Iterator for ByteLines<Reader> {
+    type Item = std::io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut line = Vec::new();
+            match self.reader.read_until(b'\n', &mut line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+            if line.ends_with(b"\n") {
+                line.pop();
+                if line.ends_with(b"\r") {
+                    line.pop();
+                }
+            }
+
+            let included =
+                self.includes.is_empty() || self.includes.iter().any(|r| r.is_match(&line));
+            if included && !self.excludes.iter().any(|r| r.is_match(&line)) {
+                return Some(Ok(line));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &[u8] = b"temp=1 \xff\xfe\r\nskip temp=2\ntemp=\xff3\n";
+
+    #[test]
+    fn lines_with_invalid_utf8_are_matched() {
+        let temp = NamedBytesRegex::new_from_string("temp", r"temp=(\d+)").unwrap();
+        let skip = Regex::new("skip").unwrap();
+        let table: DataTable<f64> =
+            extract_data_bytes(LOG, vec![temp], vec![], vec![skip.clone()], None, true).unwrap();
+        let temps = table.col_slice_by_name("temp").unwrap();
+        assert_eq!(temps[0], 1.0);
+        assert!(temps[1].is_nan());
+
+        let lines = filter_bytes(LOG, vec![skip], vec![]).unwrap();
+        assert_eq!(lines, [b"skip temp=2".to_vec()]);
+    }
+
+    #[test]
+    fn integer_rows_with_missing_values_are_skipped() {
+        let temp = NamedBytesRegex::new_from_string("temp", r"temp=(?P<t>\d+)")
+            .unwrap()
+            .with_capture(Capture::Named("t".into()));
+        let table: DataTable<i64> =
+            extract_data_bytes(LOG, vec![temp], vec![], vec![], None, false).unwrap();
+        assert_eq!(table.col_slice_by_name("temp").unwrap(), &[1, 2]);
+    }
+}
@@ -0,0 +1,70 @@
+use std::{collections::VecDeque, io::Read};
+
+/// Reads several inputs back-to-back as one stream, like rotated log files `app.log.1` and `app.log`,
+/// so lines are numbered across all of them.
+///
+/// A line break is inserted after an input which does not end with one, so its last line is not
+/// joined with the first line of the next input.
+#[derive(Debug)]
+pub struct ChainReader<R> {
+    readers: VecDeque<R>,
+    /// The last byte read from the current input.
+    last: Option<u8>,
+    /// Whether a line break has to be inserted before reading the next input.
+    separate: bool,
+}
+
+impl<R> ChainReader<R> {
+    pub fn new(readers: impl IntoIterator<Item = R>) -> Self {
+        Self {
+            readers: readers.into_iter().collect(),
+            last: None,
+            separate: false,
+        }
+    }
+
+    /// Appends `reader` to the inputs read after the current ones.
+    pub fn push(&mut self, reader: R) {
+        self.readers.push_back(reader);
+    }
+}
+
+impl<R: Read> Read for ChainReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if self.separate {
+                self.separate = false;
+                buf[0] = b'\n';
+                return Ok(1);
+            }
+            let Some(reader) = self.readers.front_mut() else {
+                return Ok(0);
+            };
+            let n = reader.read(buf)?;
+            if n > 0 {
+                self.last = Some(buf[n - 1]);
+                return Ok(n);
+            }
+            self.readers.pop_front();
+            self.separate = !self.readers.is_empty() && self.last.is_some_and(|last| last != b'\n');
+            self.last = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inputs_are_separated_by_line_breaks() {
+        let mut chain = ChainReader::new(vec![&b"a\nb"[..], b"", b"c\n"]);
+        chain.push(b"d");
+        let mut text = String::new();
+        chain.read_to_string(&mut text).unwrap();
+        assert_eq!(text, "a\nb\nc\nd");
+    }
+}
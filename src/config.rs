@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use fancy_regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+use crate::error::ConfigError;
+use crate::{
+    error::RegexBuildError,
+    extractor::{Extractor, MissingPolicy},
+    flags::RegexFlags,
+    format::NumberFormat,
+    serde_regex, NamedRegex,
+};
+
+/// A reusable description of an extraction, e.g. loaded from a profile file with
+/// [`ExtractionConfig::from_path`].
+///
+/// Regexes are given as patterns, all fields are optional. The value format and transform of
+/// each column are part of its [`NamedRegex`], the `flags` apply to all patterns.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExtractionConfig {
+    #[serde(default)]
+    pub data: Vec<NamedRegex>,
+    /// Regexes extracting text columns, see [`NamedRegex::as_text`].
+    #[serde(default)]
+    pub text: Vec<NamedRegex>,
+    /// Regexes whose values stick to the following rows, see [`Extractor::with_context_regex`].
+    #[serde(default)]
+    pub context: Vec<NamedRegex>,
+    #[serde(default, with = "serde_regex::vec")]
+    pub include: Vec<Regex>,
+    #[serde(default, with = "serde_regex::vec")]
+    pub exclude: Vec<Regex>,
+    /// The name of the column used as base data.
+    #[serde(default)]
+    pub base_data: Option<String>,
+    #[serde(default)]
+    pub group: bool,
+    #[serde(default, with = "serde_regex::option")]
+    pub record_start: Option<Regex>,
+    #[serde(default, with = "serde_regex::option")]
+    pub start_marker: Option<Regex>,
+    #[serde(default, with = "serde_regex::option")]
+    pub stop_marker: Option<Regex>,
+    #[serde(default)]
+    pub skip_lines: usize,
+    #[serde(default)]
+    pub sample_every: Option<usize>,
+    #[serde(default)]
+    pub require_matches: bool,
+    #[serde(default)]
+    pub number_format: Option<NumberFormat>,
+    #[serde(default)]
+    pub flags: RegexFlags,
+    #[serde(default)]
+    pub missing_policy: MissingPolicy,
+    /// Policies overriding `missing_policy` for single columns.
+    #[serde(default)]
+    pub column_missing_policy: HashMap<String, MissingPolicy>,
+}
+
+impl ExtractionConfig {
+    /// Parses a config from JSON.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Parses a config from TOML.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, ConfigError> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Parses a config from YAML.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, ConfigError> {
+        Ok(serde_yaml::from_str(yaml)?)
+    }
+
+    /// Loads a config from a file. The format is chosen by the file extension, ignoring its case:
+    /// `.json` (or none), `.toml` and `.yaml`/`.yml`, each if its feature is enabled.
+    #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let read = || std::fs::read_to_string(path);
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            #[cfg(feature = "json")]
+            Some("json") | None => Self::from_json(&read()?),
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml(&read()?),
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml(&read()?),
+            extension => Err(ConfigError::UnsupportedFormat(
+                extension.unwrap_or_default().to_string(),
+            )),
+        }
+    }
+
+    /// Returns an extractor configured like this, which can be configured further.
+    ///
+    /// Fails if a pattern is invalid with the `flags` applied.
+    pub fn extractor<T>(&self) -> Result<Extractor<T>, RegexBuildError> {
+        let compile = |name: &str, regex: &Regex| {
+            let pattern = self.flags.apply(regex.as_str());
+            Regex::new(&pattern).map_err(|source| RegexBuildError {
+                name: name.to_string(),
+                pattern,
+                source,
+            })
+        };
+        let mut extractor = Extractor::new()
+            .group_mode(self.group)
+            .skip_lines(self.skip_lines)
+            .require_matches(self.require_matches)
+            .missing_policy(self.missing_policy);
+        for regex in &self.data {
+            extractor = extractor.with_data_regex(regex.clone().with_flags(self.flags)?);
+        }
+        for regex in &self.text {
+            extractor = extractor.with_data_regex(regex.clone().with_flags(self.flags)?.as_text());
+        }
+        for regex in &self.context {
+            extractor = extractor.with_context_regex(regex.clone().with_flags(self.flags)?);
+        }
+        for regex in &self.include {
+            extractor = extractor.include(compile("include", regex)?);
+        }
+        for regex in &self.exclude {
+            extractor = extractor.exclude(compile("exclude", regex)?);
+        }
+        if let Some(name) = &self.base_data {
+            extractor = extractor.base_data(name);
+        }
+        if let Some(regex) = &self.record_start {
+            extractor = extractor.record_start(compile("record_start", regex)?);
+        }
+        if let Some(regex) = &self.start_marker {
+            extractor = extractor.start_marker(compile("start_marker", regex)?);
+        }
+        if let Some(regex) = &self.stop_marker {
+            extractor = extractor.stop_marker(compile("stop_marker", regex)?);
+        }
+        if let Some(stride) = self.sample_every {
+            extractor = extractor.sample_every(stride);
+        }
+        if let Some(number_format) = self.number_format {
+            extractor = extractor.number_format(number_format);
+        }
+        for (name, policy) in &self.column_missing_policy {
+            extractor = extractor.column_missing_policy(name, *policy);
+        }
+        Ok(extractor)
+    }
+}
+
+#[cfg(all(test, any(feature = "json", feature = "toml", feature = "yaml")))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn from_json_reads_patterns() {
+        let config = ExtractionConfig::from_json(
+            r#"{"data": [{"name": "temp", "regex": "T:(\\d+)"}], "base_data": "temp"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.data[0].name, "temp");
+        assert_eq!(config.data[0].regex.as_str(), r"T:(\d+)");
+        assert_eq!(config.base_data.as_deref(), Some("temp"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn from_toml_reads_patterns() {
+        let config = ExtractionConfig::from_toml(
+            "include = ['sensor']\nskip_lines = 2\n[[data]]\nname = 'temp'\nregex = 'T:(\\d+)'\n",
+        )
+        .unwrap();
+        assert_eq!(config.data[0].regex.as_str(), r"T:(\d+)");
+        assert_eq!(config.include[0].as_str(), "sensor");
+        assert_eq!(config.skip_lines, 2);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn from_yaml_reads_patterns() {
+        let config = ExtractionConfig::from_yaml(
+            "data:\n  - name: temp\n    regex: 'T:(\\d+)'\nexclude: [debug]\n",
+        )
+        .unwrap();
+        assert_eq!(config.data[0].regex.as_str(), r"T:(\d+)");
+        assert_eq!(config.exclude[0].as_str(), "debug");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn from_path_rejects_unknown_extensions() {
+        assert!(matches!(
+            ExtractionConfig::from_path("profile.ini"),
+            Err(ConfigError::UnsupportedFormat(ext)) if ext == "ini"
+        ));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn extractor_applies_all_fields() {
+        let config = ExtractionConfig::from_json(
+            r#"{
+                "data": [{"name": "temp", "regex": "t=(\\d+)"}],
+                "text": [{"name": "level", "regex": "(info|warn)"}],
+                "context": [{"name": "run", "regex": "^RUN (\\d+)"}],
+                "group": true,
+                "flags": {"case_insensitive": true, "dot_matches_newline": false},
+                "missing_policy": "SkipRow"
+            }"#,
+        )
+        .unwrap();
+        let table = config
+            .extractor::<f64>()
+            .unwrap()
+            .run("RUN 1\nT=5 INFO\nnoise\nrun 2\nt=6 warn\n".as_bytes())
+            .unwrap();
+        assert_eq!(table.col_slice_by_name("temp").unwrap(), &[5.0, 6.0]);
+        assert_eq!(table.col_slice_by_name("run").unwrap(), &[1.0, 2.0]);
+        let levels: Vec<_> = table.get_text_col_by_name("level").unwrap().collect();
+        assert_eq!(levels, ["INFO", "warn"]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn from_path_ignores_the_case_of_the_extension() {
+        let path =
+            std::env::temp_dir().join(format!("regextractor-{}-PROFILE.JSON", std::process::id()));
+        std::fs::write(&path, r#"{"skip_lines": 3}"#).unwrap();
+        let config = ExtractionConfig::from_path(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.unwrap().skip_lines, 3);
+    }
+}
@@ -0,0 +1,54 @@
+/// Reduces several values to a single one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Aggregation {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Count,
+    First,
+    Last,
+}
+
+impl Aggregation {
+    /// Applies the aggregation, returning `None` for an empty input (except for `Count`).
+    pub fn apply<T, I>(&self, values: I) -> Option<T>
+    where
+        T: Copy + num::Num + num::NumCast + PartialOrd,
+        I: IntoIterator<Item = T>,
+    {
+        let mut values = values.into_iter();
+        match self {
+            Aggregation::Count => T::from(values.count()),
+            Aggregation::First => values.next(),
+            Aggregation::Last => values.last(),
+            Aggregation::Sum => values.reduce(|a, b| a + b),
+            Aggregation::Min => values.reduce(|a, b| if b < a { b } else { a }),
+            Aggregation::Max => values.reduce(|a, b| if b > a { b } else { a }),
+            Aggregation::Mean => {
+                let (sum, count) = values.fold((None, 0usize), |(sum, count), v| {
+                    (Some(sum.map_or(v, |s| s + v)), count + 1)
+                });
+                sum.and_then(|s| Some(s / T::from(count)?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregations_of_empty_inputs_are_none_except_count() {
+        let values = [3.0, 1.0, 2.0];
+        assert_eq!(Aggregation::Sum.apply(values), Some(6.0));
+        assert_eq!(Aggregation::Mean.apply(values), Some(2.0));
+        assert_eq!(Aggregation::Min.apply(values), Some(1.0));
+        assert_eq!(Aggregation::Max.apply(values), Some(3.0));
+        assert_eq!(Aggregation::Last.apply(values), Some(2.0));
+        assert_eq!(Aggregation::Mean.apply(Vec::<i64>::new()), None);
+        assert_eq!(Aggregation::Count.apply(Vec::<i64>::new()), Some(0));
+    }
+}
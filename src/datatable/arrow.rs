@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray};
+use arrow_schema::{ArrowError, Field, Schema};
+
+use super::DataTable;
+use crate::numeric::Numeric;
+
+/// The name of the column holding the row index, for tables without base data column.
+const BASE_COLUMN: &str = "base";
+
+impl<T> DataTable<T>
+where
+    T: Numeric,
+{
+    /// Converts the table into an Arrow `RecordBatch` with one `Float64` column per value column
+    /// and one `Utf8` column per text column. Missing values become nulls.
+    ///
+    /// Tables without base data column start with a `base` column holding the base data.
+    pub fn to_arrow(&self) -> Result<RecordBatch, ArrowError> {
+        let floats = |col: &[T]| -> ArrayRef {
+            Arc::new(
+                col.iter()
+                    .map(|v| v.to_f64().filter(|_| !v.is_missing()))
+                    .collect::<Float64Array>(),
+            )
+        };
+
+        let base = match self.base_data_index {
+            Some(_) => None,
+            None => Some((BASE_COLUMN.to_string(), floats(&self.base_data))),
+        };
+        let values = self
+            .value_names
+            .iter()
+            .zip(&self.value_data)
+            .map(|(name, col)| (name.clone(), floats(col)));
+        let texts = self
+            .text_names
+            .iter()
+            .zip(&self.text_data)
+            .map(|(name, col)| {
+                let col: ArrayRef = Arc::new(StringArray::from_iter_values(col));
+                (name.clone(), col)
+            });
+        let (fields, columns): (Vec<_>, Vec<_>) = base
+            .into_iter()
+            .chain(values)
+            .chain(texts)
+            .map(|(name, col)| (Field::new(name, col.data_type().clone(), true), col))
+            .unzip();
+
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Array, Float64Array, StringArray};
+
+    use crate::datatable::builder::DataTableBuilder;
+
+    #[test]
+    fn missing_values_become_nulls() {
+        let mut builder = DataTableBuilder::<f64>::new(&["temp".into()])
+            .unwrap()
+            .with_text_columns(&["level".into()])
+            .unwrap();
+        builder.add_row(&[20.5], &["INFO".into()]).unwrap();
+        builder.add_row(&[f64::NAN], &["WARN".into()]).unwrap();
+        let batch = builder.build(None).unwrap().to_arrow().unwrap();
+
+        let names: Vec<_> = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect();
+        assert_eq!(names, ["base", "temp", "level"]);
+        let temp = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(temp.value(0), 20.5);
+        assert!(temp.is_null(1));
+        let level = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(level.value(1), "WARN");
+    }
+}
@@ -1,56 +1,134 @@
 use super::{datatable_error::DataTableError, DataTable};
+use crate::numeric::Numeric;
 
-use std::collections::HashMap;
-
-#[derive(Default)]
-pub(crate) struct DataTableBuilder<T> {
-    pub(crate) data: HashMap<String, Vec<T>>,
+/// Builds a `DataTable` from values of any source, row by row.
+///
+/// The columns are fixed when creating the builder: value columns by [`DataTableBuilder::new`]
+/// and text columns by [`DataTableBuilder::with_text_columns`], all with unique names.
+/// Every row has a value for every column: rows are either added at once by
+/// [`DataTableBuilder::add_row`] with the values in column order, or value by value by
+/// [`DataTableBuilder::add_value`] and [`DataTableBuilder::add_text`] followed by
+/// [`DataTableBuilder::end_row`], which fills the columns without value.
+///
+/// ```
+/// use regextractor::datatable::builder::DataTableBuilder;
+///
+/// let mut builder = DataTableBuilder::<f64>::new(&["time".into(), "temp".into()])?
+///     .with_text_columns(&["level".into()])?;
+/// builder.add_value("time", 0.5)?;
+/// builder.add_value("temp", 21.0)?;
+/// builder.add_text("level", "INFO")?;
+/// builder.end_row()?;
+/// builder.add_row(&[1.0, 22.5], &["WARN".into()])?;
+/// let table = builder.build(Some("time"))?;
+/// assert_eq!(table.col_slice_by_name("temp")?, &[21.0, 22.5]);
+/// # Ok::<(), regextractor::datatable::datatable_error::DataTableError>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct DataTableBuilder<T> {
+    data: Vec<(String, Vec<T>)>,
+    texts: Vec<(String, Vec<String>)>,
+    row: Vec<Option<T>>,
+    text_row: Vec<Option<String>>,
 }
 
 impl<T: Copy + num::Num> DataTableBuilder<T> {
-    pub(crate) fn new(names: &[String]) -> Result<Self, DataTableError> {
+    /// Creates a builder with the given value columns.
+    pub fn new(names: &[String]) -> Result<Self, DataTableError> {
         let mut builder = Self {
-            data: HashMap::new(),
+            data: Vec::new(),
+            texts: Vec::new(),
+            row: vec![None; names.len()],
+            text_row: Vec::new(),
         };
         for name in names {
-            if builder.data.contains_key(name) {
-                return Err(DataTableError::DuplicateName);
+            if builder.data.iter().any(|(n, _)| n == name) {
+                return Err(DataTableError::DuplicateName(name.clone()));
             }
-            builder.data.insert(name.clone(), vec![]);
+            builder.data.push((name.clone(), vec![]));
         }
         Ok(builder)
     }
 
-    pub(crate) fn add_value(&mut self, name: &str, value: T) -> Result<(), DataTableError> {
-        if let Some(col) = self.data.get_mut(name) {
-            col.push(value);
-            Ok(())
-        } else {
-            Err(DataTableError::InvalidColumnName)
+    /// Adds text columns, which hold strings like labels or log levels next to the numeric values.
+    pub fn with_text_columns(mut self, names: &[String]) -> Result<Self, DataTableError> {
+        for name in names {
+            if self.data.iter().any(|(n, _)| n == name) || self.texts.iter().any(|(n, _)| n == name)
+            {
+                return Err(DataTableError::DuplicateName(name.clone()));
+            }
+            self.texts.push((name.clone(), vec![]));
+            self.text_row.push(None);
         }
+        Ok(self)
+    }
+
+    /// Reserves space for `rows` rows in every column, e.g. estimated from the input size.
+    ///
+    /// Only columns added before are affected.
+    pub fn with_capacity(mut self, rows: usize) -> Self {
+        self.reserve(rows);
+        self
+    }
+
+    /// Reserves space for at least `additional` more rows in every column.
+    pub fn reserve(&mut self, additional: usize) {
+        self.data
+            .iter_mut()
+            .for_each(|(_, c)| c.reserve(additional));
+        self.texts
+            .iter_mut()
+            .for_each(|(_, c)| c.reserve(additional));
     }
 
-    pub(crate) fn build(
-        self,
-        base_data_name: Option<&str>,
-    ) -> Result<DataTable<T>, DataTableError> {
+    /// Adds one value to every column, in column order.
+    pub fn add_row(&mut self, values: &[T], texts: &[String]) -> Result<(), DataTableError> {
+        if values.len() != self.data.len() || texts.len() != self.texts.len() {
+            return Err(DataTableError::InvalidColumnCount {
+                expected: self.data.len() + self.texts.len(),
+                actual: values.len() + texts.len(),
+            });
+        }
+        for ((_, col), value) in self.data.iter_mut().zip(values) {
+            col.push(*value);
+        }
+        for ((_, col), text) in self.texts.iter_mut().zip(texts) {
+            col.push(text.clone());
+        }
+        Ok(())
+    }
+
+    /// Creates the table, using the value column `base_data_name` as base data if given.
+    ///
+    /// Fails if a row was started by `add_value` or `add_text` but not ended.
+    pub fn build(self, base_data_name: Option<&str>) -> Result<DataTable<T>, DataTableError> {
+        if self.row.iter().any(Option::is_some) || self.text_row.iter().any(Option::is_some) {
+            return Err(DataTableError::InconsistentBuilderData);
+        }
         let len = self.get_len()?;
 
-        let names: Vec<_> = self.data.keys().cloned().collect();
+        let names: Vec<_> = self.data.iter().map(|(n, _)| n.clone()).collect();
+        let text_names: Vec<_> = self.texts.iter().map(|(n, _)| n.clone()).collect();
         let mut dt = if let Some(name) = base_data_name {
-            DataTable::<T>::new_with_base_data_name(self.data.keys().count(), &names, name)?
+            DataTable::<T>::new_with_base_data_name(names.len(), &names, name)?
         } else {
-            DataTable::<T>::new(self.data.keys().count(), Some(&names), None)
-        };
+            DataTable::<T>::new(names.len(), Some(&names), None)
+        }
+        .with_text_columns(&text_names);
+        dt.reserve(len);
 
         for i in 0..len {
-            dt.add_row(&self.get_row(i)?).ok();
+            dt.add_row(&self.get_row(i)?, &self.get_text_row(i)?).ok();
         }
         Ok(dt)
     }
 
     fn get_len(&self) -> Result<usize, DataTableError> {
-        let mut lens = self.data.values().map(|c| c.len());
+        let mut lens = self
+            .data
+            .iter()
+            .map(|(_, c)| c.len())
+            .chain(self.texts.iter().map(|(_, c)| c.len()));
         if lens.clone().max() != lens.clone().min() {
             return Err(DataTableError::InconsistentBuilderData);
         }
@@ -59,9 +137,96 @@ impl<T: Copy + num::Num> DataTableBuilder<T> {
 
     fn get_row(&self, index: usize) -> Result<Vec<T>, DataTableError> {
         self.data
-            .values()
-            .map(|v| v.get(index).cloned())
+            .iter()
+            .map(|(_, v)| v.get(index).cloned())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(DataTableError::InvalidRowIndex(index))
+    }
+
+    fn get_text_row(&self, index: usize) -> Result<Vec<String>, DataTableError> {
+        self.texts
+            .iter()
+            .map(|(_, v)| v.get(index).cloned())
             .collect::<Option<Vec<_>>>()
-            .ok_or(DataTableError::InvalidColumnIndex)
+            .ok_or(DataTableError::InvalidRowIndex(index))
+    }
+}
+
+impl<T: Numeric> DataTableBuilder<T> {
+    /// Sets the value of the named column in the current row, see [`DataTableBuilder::end_row`].
+    pub fn add_value(&mut self, name: &str, value: T) -> Result<(), DataTableError> {
+        let index = self
+            .data
+            .iter()
+            .position(|(n, _)| n == name)
+            .ok_or_else(|| DataTableError::InvalidColumnName(name.to_string()))?;
+        self.row[index] = Some(value);
+        Ok(())
+    }
+
+    /// Sets the text of the named text column in the current row, see [`DataTableBuilder::end_row`].
+    pub fn add_text(&mut self, name: &str, text: &str) -> Result<(), DataTableError> {
+        let index = self
+            .texts
+            .iter()
+            .position(|(n, _)| n == name)
+            .ok_or_else(|| DataTableError::InvalidColumnName(name.to_string()))?;
+        self.text_row[index] = Some(text.to_string());
+        Ok(())
+    }
+
+    /// Adds the current row. Value columns without value are missing (NaN) and text columns without
+    /// text are empty.
+    ///
+    /// Fails with `DataTableError::MissingValue` if a value is missing and `T` is an integer type,
+    /// which cannot hold missing values. The row is kept in this case, so the value can still be added.
+    pub fn end_row(&mut self) -> Result<(), DataTableError> {
+        let values = self
+            .row
+            .iter()
+            .zip(&self.data)
+            .map(|(value, (name, _))| {
+                value
+                    .or_else(T::missing)
+                    .ok_or_else(|| DataTableError::MissingValue(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let texts: Vec<_> = self
+            .text_row
+            .iter_mut()
+            .map(|text| text.take().unwrap_or_default())
+            .collect();
+        self.row.iter_mut().for_each(|value| *value = None);
+        self.add_row(&values, &texts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_are_built_value_by_value() {
+        let mut builder = DataTableBuilder::<i64>::new(&["time".into(), "count".into()])
+            .unwrap()
+            .with_text_columns(&["level".into()])
+            .unwrap();
+        builder.add_value("time", 1).unwrap();
+        builder.add_text("level", "WARN").unwrap();
+        assert!(matches!(
+            builder.end_row(),
+            Err(DataTableError::MissingValue(name)) if name == "count"
+        ));
+        builder.add_value("count", 4).unwrap();
+        builder.end_row().unwrap();
+        builder.add_value("time", 2).unwrap();
+        builder.add_value("count", 5).unwrap();
+        builder.end_row().unwrap();
+        assert!(builder.add_value("pressure", 1).is_err());
+
+        let data = builder.build(Some("time")).unwrap();
+        assert_eq!(data.col_slice_by_name("count").unwrap(), &[4, 5]);
+        let level: Vec<_> = data.get_text_col_by_name("level").unwrap().collect();
+        assert_eq!(level, ["WARN", ""]);
     }
 }
@@ -0,0 +1,88 @@
+use super::DataTable;
+use crate::numeric::Numeric;
+
+impl<T: Numeric> DataTable<T> {
+    /// Returns `true` if both tables have the same column names, rows, base data and texts,
+    /// and their values differ by at most `epsilon`. Missing values only equal missing values.
+    ///
+    /// Useful to test extraction configs, see also [`assert_table_eq!`](crate::assert_table_eq).
+    pub fn approx_eq(&self, other: &DataTable<T>, epsilon: f64) -> bool {
+        let close = |a: &T, b: &T| match (a.is_missing(), b.is_missing()) {
+            (true, true) => true,
+            (false, false) => a
+                .to_f64()
+                .zip(b.to_f64())
+                .is_some_and(|(a, b)| a == b || (a - b).abs() <= epsilon),
+            _ => false,
+        };
+        let columns_close =
+            |a: &[T], b: &[T]| a.len() == b.len() && a.iter().zip(b).all(|(a, b)| close(a, b));
+
+        self.value_rows == other.value_rows
+            && self.value_names == other.value_names
+            && self.text_names == other.text_names
+            && self.text_data == other.text_data
+            && self.base_data_index == other.base_data_index
+            && columns_close(self.get_base_data(), other.get_base_data())
+            && self
+                .value_data
+                .iter()
+                .zip(&other.value_data)
+                .all(|(a, b)| columns_close(a, b))
+    }
+}
+
+/// Asserts that two tables are equal with [`DataTable::approx_eq`], printing both tables otherwise.
+///
+/// The tolerance defaults to `1e-9`.
+///
+/// ```
+/// use regextractor::{assert_table_eq, datatable::DataTable};
+///
+/// let table = DataTable::<f64>::from_columns(vec![("a".into(), vec![0.1 + 0.2])], None)?;
+/// let expected = DataTable::<f64>::from_columns(vec![("a".into(), vec![0.3])], None)?;
+/// assert_table_eq!(table, expected);
+/// assert_table_eq!(table, expected, 1e-12);
+/// # Ok::<(), regextractor::datatable::datatable_error::DataTableError>(())
+/// ```
+#[macro_export]
+macro_rules! assert_table_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_table_eq!($left, $right, 1e-9)
+    };
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {{
+        let (left, right) = (&$left, &$right);
+        if !left.approx_eq(right, $epsilon) {
+            panic!(
+                "assertion failed: tables are not equal\n left:\n{}\nright:\n{}",
+                left.preview(usize::MAX),
+                right.preview(usize::MAX)
+            );
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(temp: &[f64]) -> DataTable<f64> {
+        DataTable::from_columns(vec![("temp".into(), temp.to_vec())], None).unwrap()
+    }
+
+    #[test]
+    fn values_are_compared_with_a_tolerance() {
+        let data = table(&[1.0, f64::NAN]);
+        assert!(data.approx_eq(&table(&[1.0, f64::NAN]), 0.0));
+        assert!(data.approx_eq(&table(&[1.05, f64::NAN]), 0.1));
+        assert!(!data.approx_eq(&table(&[1.05, f64::NAN]), 0.01));
+        assert!(!data.approx_eq(&table(&[1.0, 2.0]), 0.1));
+        assert!(!data.approx_eq(&table(&[1.0]), 0.1));
+    }
+
+    #[test]
+    #[should_panic(expected = "tables are not equal")]
+    fn assert_table_eq_panics_for_different_tables() {
+        crate::assert_table_eq!(table(&[1.0]), table(&[2.0]));
+    }
+}
@@ -0,0 +1,161 @@
+use std::{borrow::Cow, fmt::Display, io};
+
+use super::DataTable;
+use crate::numeric::Numeric;
+
+/// Options for [`DataTable::write_csv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Separates the fields of a row.
+    pub delimiter: char,
+    /// Writes a first row with the column names.
+    pub header: bool,
+    /// The number of decimal places of floating point values, all significant ones if `None`.
+    pub precision: Option<usize>,
+    /// Written instead of missing values.
+    pub nan_placeholder: String,
+    /// Appends the unit of the column metadata to the column names like `temp [°C]`.
+    pub units_in_header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            header: true,
+            precision: None,
+            nan_placeholder: "NaN".into(),
+            units_in_header: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Writes the header row with the column `names`, ignoring `header`.
+    pub fn write_header(
+        &self,
+        writer: &mut impl io::Write,
+        names: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> io::Result<()> {
+        let names: Vec<_> = names
+            .into_iter()
+            .map(|name| self.quote(name.as_ref()).into_owned())
+            .collect();
+        writeln!(writer, "{}", names.join(&self.delimiter.to_string()))
+    }
+
+    /// Writes a row with the `values` of the value columns followed by the `texts` of the text columns,
+    /// e.g. to write rows one by one while they are extracted.
+    pub fn write_row<T: Numeric + Display>(
+        &self,
+        writer: &mut impl io::Write,
+        values: impl IntoIterator<Item = T>,
+        texts: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> io::Result<()> {
+        let values = values.into_iter().map(|value| self.format_value(value));
+        let texts = texts
+            .into_iter()
+            .map(|text| self.quote(text.as_ref()).into_owned());
+        let fields: Vec<_> = values.chain(texts).collect();
+        writeln!(writer, "{}", fields.join(&self.delimiter.to_string()))
+    }
+
+    /// Quotes `text` if it contains the delimiter, quotes or line breaks.
+    fn quote<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if text.contains([self.delimiter, '"', '\n', '\r']) {
+            Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// Formats `value` with the configured precision, or as placeholder if it is missing.
+    fn format_value<T: Numeric + Display>(&self, value: T) -> String {
+        match self.precision {
+            _ if value.is_missing() => self.nan_placeholder.clone(),
+            Some(precision) => format!("{:.*}", precision, value),
+            None => value.to_string(),
+        }
+    }
+}
+
+impl<T> DataTable<T>
+where
+    T: Numeric + Display,
+{
+    /// Writes the value columns followed by the text columns as CSV.
+    ///
+    /// Fields containing the delimiter, quotes or line breaks are quoted.
+    pub fn write_csv(&self, writer: &mut impl io::Write, options: CsvOptions) -> io::Result<()> {
+        if options.header {
+            let names = self.value_names.iter().chain(&self.text_names).map(|n| {
+                if options.units_in_header {
+                    self.name_with_unit(n)
+                } else {
+                    n.clone()
+                }
+            });
+            options.write_header(writer, names)?;
+        }
+
+        for row in 0..self.value_rows {
+            let values = self.value_data.iter().map(|col| col[row]);
+            let texts = self.text_data.iter().map(|col| &col[row]);
+            options.write_row(writer, values, texts)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::datatable::builder::DataTableBuilder;
+
+    #[test]
+    fn write_csv_quotes_and_formats_fields() {
+        let mut builder = DataTableBuilder::<f64>::new(&["time".into(), "temp".into()])
+            .unwrap()
+            .with_text_columns(&["msg".into()])
+            .unwrap();
+        builder
+            .add_row(&[1.0, 20.25], &["a;b \"c\"".into()])
+            .unwrap();
+        builder.add_row(&[2.0, f64::NAN], &["ok".into()]).unwrap();
+        let table = builder.build(Some("time")).unwrap();
+
+        let options = CsvOptions {
+            delimiter: ';',
+            precision: Some(1),
+            nan_placeholder: String::new(),
+            ..Default::default()
+        };
+        let mut csv = Vec::new();
+        table.write_csv(&mut csv, options).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "time;temp;msg\n1.0;20.2;\"a;b \"\"c\"\"\"\n2.0;;ok\n"
+        );
+    }
+
+    #[test]
+    fn quote_only_quotes_when_needed() {
+        let options = CsvOptions::default();
+        assert_eq!(options.quote("plain"), "plain");
+        assert_eq!(options.quote("a,b"), "\"a,b\"");
+        assert_eq!(options.quote("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn rows_are_written_one_by_one() {
+        let options = CsvOptions {
+            precision: Some(2),
+            ..Default::default()
+        };
+        let mut csv = Vec::new();
+        options.write_header(&mut csv, ["t", "a,b"]).unwrap();
+        options.write_row(&mut csv, [1.0, f64::NAN], ["x"]).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "t,\"a,b\"\n1.00,NaN,x\n");
+    }
+}
@@ -0,0 +1,124 @@
+use polars::prelude::{Column, DataFrame, DataType, NamedFrom, PolarsError, PolarsResult, Series};
+
+use super::DataTable;
+use crate::numeric::Numeric;
+
+impl<T> From<DataTable<T>> for DataFrame
+where
+    T: Numeric,
+{
+    /// Converts the value columns into `Float64` columns, missing values into nulls,
+    /// and the text columns into `String` columns.
+    ///
+    /// # Panics
+    /// Panics if the column names are not unique.
+    fn from(table: DataTable<T>) -> Self {
+        let values = table
+            .value_names
+            .iter()
+            .zip(&table.value_data)
+            .map(|(name, col)| {
+                let col: Vec<_> = col
+                    .iter()
+                    .map(|v| v.to_f64().filter(|_| !v.is_missing()))
+                    .collect();
+                Column::from(Series::new(name.into(), col))
+            });
+        let texts = table
+            .text_names
+            .iter()
+            .zip(&table.text_data)
+            .map(|(name, col)| Column::from(Series::new(name.into(), col)));
+        DataFrame::new(values.chain(texts).collect()).expect("The column names are unique.")
+    }
+}
+
+impl<T> TryFrom<DataFrame> for DataTable<T>
+where
+    T: Numeric,
+{
+    type Error = PolarsError;
+
+    /// Converts numeric and boolean columns into value columns and string columns into text columns.
+    /// Nulls become missing values, or empty strings in text columns.
+    ///
+    /// Fails for columns of other types, or for nulls in integer tables.
+    fn try_from(frame: DataFrame) -> PolarsResult<Self> {
+        let mut value_names = vec![];
+        let mut value_data = vec![];
+        let mut text_names = vec![];
+        let mut text_data = vec![];
+        for col in frame.get_columns() {
+            let name = col.name().to_string();
+            let series = col.as_materialized_series();
+            let dtype = series.dtype();
+            if dtype.is_primitive_numeric() || dtype.is_bool() {
+                let values = series
+                    .cast(&DataType::Float64)?
+                    .f64()?
+                    .iter()
+                    .map(|v| {
+                        v.and_then(T::from).or_else(T::missing).ok_or_else(|| {
+                            PolarsError::ComputeError(
+                                format!(
+                                    "column '{}' has nulls, which the value type cannot hold",
+                                    name
+                                )
+                                .into(),
+                            )
+                        })
+                    })
+                    .collect::<PolarsResult<_>>()?;
+                value_names.push(name);
+                value_data.push(values);
+            } else if dtype.is_string() {
+                let texts = series
+                    .str()?
+                    .iter()
+                    .map(|t| t.unwrap_or_default().to_string())
+                    .collect();
+                text_names.push(name);
+                text_data.push(texts);
+            } else {
+                return Err(PolarsError::SchemaMismatch(
+                    format!("column '{}' has the unsupported type {}", name, dtype).into(),
+                ));
+            }
+        }
+
+        let rows = frame.height();
+        let mut table = DataTable::new(value_names.len(), Some(&value_names), None)
+            .with_text_columns(&text_names);
+        table.value_rows = rows;
+        table.value_data = value_data;
+        table.text_data = text_data;
+        table.base_data = (0..rows).filter_map(T::from).collect();
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::df;
+
+    use super::*;
+
+    #[test]
+    fn frames_are_converted_both_ways() {
+        let table =
+            DataTable::<f64>::from_columns(vec![("temp".into(), vec![20.5, f64::NAN])], None)
+                .unwrap();
+        let frame = DataFrame::from(table);
+        assert_eq!(frame.column("temp").unwrap().null_count(), 1);
+
+        let back = DataTable::<f64>::try_from(frame).unwrap();
+        assert_eq!(back.col_slice_by_name("temp").unwrap()[0], 20.5);
+        assert!(back.col_slice_by_name("temp").unwrap()[1].is_nan());
+
+        let frame = df!("count" => [Some(1i64), None], "level" => ["INFO", "WARN"]).unwrap();
+        let texts = DataTable::<f64>::try_from(frame.clone()).unwrap();
+        let level: Vec<_> = texts.get_text_col_by_name("level").unwrap().collect();
+        assert_eq!(level, ["INFO", "WARN"]);
+        assert!(DataTable::<i64>::try_from(frame).is_err());
+    }
+}
@@ -1,21 +1,31 @@
-use std::error::Error;
+use thiserror::Error;
 
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum DataTableError {
-    InvalidColumnName,
-    InvalidColumnCount,
-    InvalidColumnIndex,
-    InvalidCBaseDataIndex,
-    InvalidCBaseDataName,
+    #[error("there is no column named '{0}'")]
+    InvalidColumnName(String),
+    #[error("expected {expected} values per row, got {actual}")]
+    InvalidColumnCount { expected: usize, actual: usize },
+    #[error("column index {0} is out of range")]
+    InvalidColumnIndex(usize),
+    #[error("row index {0} is out of range")]
+    InvalidRowIndex(usize),
+    #[error("base data index {0} is out of range")]
+    InvalidCBaseDataIndex(usize),
+    #[error("there is no base data column named '{0}'")]
+    InvalidCBaseDataName(String),
+    #[error("the columns have different lengths")]
     InconsistentBuilderData,
-    InconsistentContainerSize,
-    DuplicateName,
+    #[error("the tables have different base data columns")]
+    InconsistentBaseData,
+    #[error("the containers have different sizes, {0} and {1}")]
+    InconsistentContainerSize(usize, usize),
+    #[error("the row has no value for column '{0}', which the value type cannot hold")]
+    MissingValue(String),
+    #[error("the quantile {0} is not between 0 and 1")]
+    InvalidQuantile(f64),
+    #[error("a histogram needs at least one bin")]
+    NoBins,
+    #[error("the column name '{0}' is used more than once")]
+    DuplicateName(String),
 }
-
-impl std::fmt::Display for DataTableError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
-    }
-}
-
-impl Error for DataTableError {}
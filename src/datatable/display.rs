@@ -0,0 +1,167 @@
+use std::fmt::{self, Display};
+
+use super::DataTable;
+use crate::numeric::Numeric;
+
+/// The number of rows shown when displaying a `DataTable`, see [`DataTable::preview`] for other limits.
+const DISPLAY_ROWS: usize = 20;
+
+/// Renders the first rows of a table, see [`DataTable::preview`].
+pub struct Preview<'a, T> {
+    table: &'a DataTable<T>,
+    rows: usize,
+}
+
+impl<T> DataTable<T>
+where
+    T: Numeric + Display,
+{
+    /// Returns a displayable aligned table with the column names and the first `rows` rows.
+    pub fn preview(&self, rows: usize) -> Preview<'_, T> {
+        Preview { table: self, rows }
+    }
+}
+
+impl<T> Display for Preview<'_, T>
+where
+    T: Numeric + Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let table = self.table;
+        let rows = self.rows.min(table.value_rows);
+        let columns = AlignedColumns::new(table, rows, |v| v.to_string(), str::to_string, 0);
+
+        writeln!(f, "{}", columns.header().join(" | ").trim_end())?;
+        let separator: Vec<_> = columns.widths().map(|(w, _)| "-".repeat(w)).collect();
+        writeln!(f, "{}", separator.join("-+-"))?;
+        for row in 0..rows {
+            writeln!(f, "{}", columns.row(row).join(" | ").trim_end())?;
+        }
+        if table.value_rows > rows {
+            writeln!(f, "... {} more rows", table.value_rows - rows)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Display for DataTable<T>
+where
+    T: Numeric + Display,
+{
+    /// Renders an aligned table with the column names and the first rows.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.preview(DISPLAY_ROWS).fmt(f)
+    }
+}
+
+/// The value columns followed by the text columns of a table, formatted as text and padded to the
+/// width of their longest cell. Value columns are right-aligned, text columns left-aligned.
+pub(crate) struct AlignedColumns {
+    columns: Vec<AlignedColumn>,
+}
+
+struct AlignedColumn {
+    name: String,
+    cells: Vec<String>,
+    right: bool,
+    width: usize,
+}
+
+impl AlignedColumns {
+    /// Formats the first `rows` rows of `table` with `value`, passing all names and cells through `escape`.
+    /// Columns are at least `min_width` characters wide.
+    pub fn new<T: Numeric>(
+        table: &DataTable<T>,
+        rows: usize,
+        value: impl Fn(&T) -> String,
+        escape: impl Fn(&str) -> String,
+        min_width: usize,
+    ) -> Self {
+        let values = table
+            .value_names
+            .iter()
+            .zip(&table.value_data)
+            .map(|(name, col)| {
+                let cells = col[..rows].iter().map(|v| escape(&value(v))).collect();
+                (name, cells, true)
+            });
+        let texts = table
+            .text_names
+            .iter()
+            .zip(&table.text_data)
+            .map(|(name, col)| (name, col[..rows].iter().map(|t| escape(t)).collect(), false));
+        let columns = values
+            .chain(texts)
+            .map(|(name, cells, right): (_, Vec<String>, _)| {
+                let name = escape(name);
+                let width = cells
+                    .iter()
+                    .chain([&name])
+                    .map(|c| c.chars().count())
+                    .max()
+                    .unwrap_or_default()
+                    .max(min_width);
+                AlignedColumn {
+                    name,
+                    cells,
+                    right,
+                    width,
+                }
+            })
+            .collect();
+        Self { columns }
+    }
+
+    /// Returns the padded column names.
+    pub fn header(&self) -> Vec<String> {
+        self.pad(|column| &column.name)
+    }
+
+    /// Returns the padded cells of the row `row`.
+    pub fn row(&self, row: usize) -> Vec<String> {
+        self.pad(|column| &column.cells[row])
+    }
+
+    /// Returns the width of each column and whether it is right-aligned.
+    pub fn widths(&self) -> impl Iterator<Item = (usize, bool)> + '_ {
+        self.columns
+            .iter()
+            .map(|column| (column.width, column.right))
+    }
+
+    fn pad<'a>(&'a self, cell: impl Fn(&'a AlignedColumn) -> &'a String) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|column| {
+                if column.right {
+                    format!("{:>width$}", cell(column), width = column.width)
+                } else {
+                    format!("{:<width$}", cell(column), width = column.width)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datatable::builder::DataTableBuilder;
+
+    #[test]
+    fn preview_aligns_the_columns() {
+        let mut builder = DataTableBuilder::<f64>::new(&["time".into(), "temp".into()])
+            .unwrap()
+            .with_text_columns(&["level".into()])
+            .unwrap();
+        builder.add_row(&[1.0, 20.5], &["INFO".into()]).unwrap();
+        builder.add_row(&[10.0, 9.0], &["WARN".into()]).unwrap();
+        let data = builder.build(None).unwrap();
+        assert_eq!(
+            data.preview(1).to_string(),
+            "time | temp | level\n\
+             -----+------+------\n   \
+             1 | 20.5 | INFO\n\
+             ... 1 more rows\n"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use std::{fmt::Display, io};
+
+use super::DataTable;
+use crate::numeric::Numeric;
+
+impl<T> DataTable<T>
+where
+    T: Numeric + Display,
+{
+    /// Writes a gnuplot script with the table as inline data block and a `plot` command
+    /// drawing every value column against the base data, e.g. for `gnuplot -p table.gp`.
+    pub fn to_gnuplot(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        let base_name = self
+            .base_data_index
+            .map_or("row", |index| self.value_names[index].as_str());
+        let columns: Vec<usize> = (0..self.value_columns)
+            .filter(|i| Some(*i) != self.base_data_index)
+            .collect();
+        let value = |value: T| {
+            if value.is_missing() {
+                "NaN".to_string()
+            } else {
+                value.to_string()
+            }
+        };
+
+        writeln!(writer, "$data << EOD")?;
+        let names: Vec<_> = columns
+            .iter()
+            .map(|i| quote(&self.value_names[*i]))
+            .collect();
+        writeln!(writer, "# {} {}", quote(base_name), names.join(" "))?;
+        for (row, base) in self.get_base_data().iter().enumerate() {
+            let values: Vec<_> = columns
+                .iter()
+                .map(|i| value(self.value_data[*i][row]))
+                .collect();
+            writeln!(writer, "{} {}", value(*base), values.join(" "))?;
+        }
+        writeln!(writer, "EOD")?;
+
+        writeln!(writer, "set datafile missing \"NaN\"")?;
+        writeln!(writer, "set xlabel {}", quote(base_name))?;
+        let series: Vec<_> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| format!("$data using 1:{} with lines title {}", i + 2, name))
+            .collect();
+        writeln!(writer, "plot {}", series.join(", \\\n     "))
+    }
+}
+
+/// Quotes a name as gnuplot string.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_contains_the_data_block_and_plot_command() {
+        let data = DataTable::<f64>::from_columns(
+            vec![
+                ("time".into(), vec![1.0, 2.0]),
+                ("temp".into(), vec![20.5, f64::NAN]),
+                ("say \"hi\"".into(), vec![1.0, 0.0]),
+            ],
+            Some("time"),
+        )
+        .unwrap();
+        let mut script = vec![];
+        data.to_gnuplot(&mut script).unwrap();
+        assert_eq!(
+            String::from_utf8(script).unwrap(),
+            "$data << EOD\n\
+             # \"time\" \"temp\" \"say \\\"hi\\\"\"\n\
+             1 20.5 1\n\
+             2 NaN 0\n\
+             EOD\n\
+             set datafile missing \"NaN\"\n\
+             set xlabel \"time\"\n\
+             plot $data using 1:2 with lines title \"temp\", \\\n     \
+             $data using 1:3 with lines title \"say \\\"hi\\\"\"\n"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use super::{aggregation::Aggregation, datatable_error::DataTableError, DataTable};
+use crate::numeric::Numeric;
+
+/// The column the rows are grouped by.
+#[derive(PartialEq)]
+enum Key {
+    Value(usize),
+    Text(usize),
+}
+
+impl<T: Numeric> DataTable<T> {
+    /// Groups the rows by the distinct values of the value or text column `column` and reduces
+    /// every other value column per group by `aggregation`, e.g. the mean extrusion rate per tool id.
+    ///
+    /// The result has one row per group in order of first appearance, the group column first.
+    /// A numeric group column is the base data of the result. Rows without group value are dropped,
+    /// missing values are ignored by the aggregation. Other text columns are dropped.
+    pub fn group_by(
+        &self,
+        column: &str,
+        aggregation: Aggregation,
+    ) -> Result<DataTable<T>, DataTableError> {
+        let (key, groups) = if let Some(index) = self.value_names.iter().position(|n| n == column) {
+            (
+                Key::Value(index),
+                self.numeric_groups(&self.value_data[index]),
+            )
+        } else if let Some(index) = self.text_names.iter().position(|n| n == column) {
+            (Key::Text(index), self.text_groups(&self.text_data[index]))
+        } else {
+            return Err(DataTableError::InvalidColumnName(column.to_string()));
+        };
+
+        let mut columns = vec![];
+        for (index, (name, data)) in self.value_names.iter().zip(&self.value_data).enumerate() {
+            if key == Key::Value(index) {
+                continue;
+            }
+            let values = groups
+                .iter()
+                .map(|(_, rows)| {
+                    let values = rows
+                        .iter()
+                        .map(|row| data[*row])
+                        .filter(|v| !v.is_missing());
+                    aggregation
+                        .apply(values)
+                        .or_else(T::missing)
+                        .unwrap_or_else(T::zero)
+                })
+                .collect();
+            columns.push((name.clone(), values));
+        }
+
+        let mut table = match key {
+            Key::Value(index) => {
+                let keys = groups.iter().map(|(row, _)| self.value_data[index][*row]);
+                columns.insert(0, (column.to_string(), keys.collect()));
+                DataTable::from_columns(columns, Some(column))?
+            }
+            Key::Text(index) => {
+                let keys = groups
+                    .iter()
+                    .map(|(row, _)| self.text_data[index][*row].clone());
+                let mut table = DataTable::from_columns(columns, None)?;
+                table.text_names = vec![column.to_string()];
+                table.text_data = vec![keys.collect()];
+                table
+            }
+        };
+        table.metadata = self.metadata.clone();
+        table
+            .metadata
+            .retain(|name, _| name == column || table.value_names.contains(name));
+        Ok(table)
+    }
+
+    /// Returns the first row and all rows of each group of equal values, in order of first appearance.
+    fn numeric_groups(&self, keys: &[T]) -> Vec<(usize, Vec<usize>)> {
+        let mut groups: Vec<(usize, Vec<usize>)> = vec![];
+        // group indices sorted by key, to find the group of a row quickly
+        let mut sorted: Vec<usize> = vec![];
+        for (row, key) in keys.iter().enumerate().filter(|(_, k)| !k.is_missing()) {
+            let pos = sorted.partition_point(|g| keys[groups[*g].0] < *key);
+            match sorted.get(pos) {
+                Some(g) if keys[groups[*g].0] == *key => groups[*g].1.push(row),
+                _ => {
+                    sorted.insert(pos, groups.len());
+                    groups.push((row, vec![row]));
+                }
+            }
+        }
+        groups
+    }
+
+    fn text_groups(&self, keys: &[String]) -> Vec<(usize, Vec<usize>)> {
+        let mut groups: Vec<(usize, Vec<usize>)> = vec![];
+        let mut index = HashMap::new();
+        for (row, key) in keys.iter().enumerate() {
+            let group = *index.entry(key.as_str()).or_insert_with(|| {
+                groups.push((row, vec![]));
+                groups.len() - 1
+            });
+            groups[group].1.push(row);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_by_value_column_aggregates_the_others() {
+        let data = DataTable::from_columns(
+            vec![
+                ("tool".into(), vec![1.0, 0.0, 1.0, f64::NAN]),
+                ("rate".into(), vec![2.0, 5.0, f64::NAN, 9.0]),
+                ("temp".into(), vec![200.0, 210.0, 220.0, 230.0]),
+            ],
+            None,
+        )
+        .unwrap();
+        let grouped = data.group_by("tool", Aggregation::Mean).unwrap();
+        assert_eq!(grouped.col_slice_by_name("tool").unwrap(), &[1.0, 0.0]);
+        assert_eq!(grouped.col_slice_by_name("rate").unwrap(), &[2.0, 5.0]);
+        assert_eq!(grouped.col_slice_by_name("temp").unwrap(), &[210.0, 210.0]);
+        assert_eq!(grouped.get_base_data(), &[1.0, 0.0]);
+    }
+
+    #[test]
+    fn group_by_text_column_counts_rows() {
+        let mut data =
+            DataTable::from_columns(vec![("temp".into(), vec![1.0, 2.0, 3.0])], None).unwrap();
+        data.text_names = vec!["level".into()];
+        data.text_data = vec![vec!["INFO".into(), "WARN".into(), "INFO".into()]];
+
+        let grouped = data.group_by("level", Aggregation::Count).unwrap();
+        let levels: Vec<_> = grouped.get_text_col_by_name("level").unwrap().collect();
+        assert_eq!(levels, ["INFO", "WARN"]);
+        assert_eq!(grouped.col_slice_by_name("temp").unwrap(), &[2.0, 1.0]);
+        assert!(data.group_by("host", Aggregation::Count).is_err());
+    }
+}
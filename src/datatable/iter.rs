@@ -1,4 +1,6 @@
-use super::datatable_error::DataTableError;
+use std::ops::Range;
+
+use super::{datatable_error::DataTableError, DataTable};
 
 pub(crate) struct ZipIter<'a, T> {
     pub(crate) first: &'a Vec<T>,
@@ -9,7 +11,10 @@ pub(crate) struct ZipIter<'a, T> {
 impl<'a, T> ZipIter<'a, T> {
     pub fn new(first: &'a Vec<T>, second: &'a Vec<T>) -> Result<Self, DataTableError> {
         if first.len() != second.len() {
-            return Err(DataTableError::InconsistentContainerSize);
+            return Err(DataTableError::InconsistentContainerSize(
+                first.len(),
+                second.len(),
+            ));
         }
         Ok(Self {
             first,
@@ -30,3 +35,93 @@ impl<'a, T> Iterator for ZipIter<'a, T> {
         Some((&self.first[self.index - 1], &self.second[self.index - 1]))
     }
 }
+
+/// A row of a [`DataTable`], borrowing its values.
+#[derive(Debug, Clone, Copy)]
+pub struct TableRow<'a, T> {
+    table: &'a DataTable<T>,
+    index: usize,
+}
+
+impl<'a, T: Copy + num::Num> TableRow<'a, T> {
+    /// Returns the index of the row in the table.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the base data of the row.
+    pub fn base(&self) -> T {
+        self.table.get_base_data()[self.index]
+    }
+
+    /// Returns the value of the value column with the given name.
+    pub fn get(&self, name: &str) -> Option<T> {
+        let column = self.table.value_names.iter().position(|n| n == name)?;
+        Some(self.table.value_data[column][self.index])
+    }
+
+    /// Returns the values of all value columns, in column order.
+    pub fn values(&self) -> impl Iterator<Item = T> + 'a {
+        let index = self.index;
+        self.table.value_data.iter().map(move |col| col[index])
+    }
+
+    /// Returns the text of the text column with the given name.
+    pub fn get_text(&self, name: &str) -> Option<&'a str> {
+        let column = self.table.text_names.iter().position(|n| n == name)?;
+        Some(&self.table.text_data[column][self.index])
+    }
+
+    /// Returns the texts of all text columns, in column order.
+    pub fn texts(&self) -> impl Iterator<Item = &'a str> + 'a {
+        let index = self.index;
+        self.table
+            .text_data
+            .iter()
+            .map(move |col| col[index].as_str())
+    }
+}
+
+/// An iterator over the rows of a [`DataTable`], see [`DataTable::iter`].
+#[derive(Debug, Clone)]
+pub struct Rows<'a, T> {
+    table: &'a DataTable<T>,
+    range: Range<usize>,
+}
+
+impl<'a, T> Rows<'a, T> {
+    pub(crate) fn new(table: &'a DataTable<T>) -> Self {
+        Self {
+            table,
+            range: 0..table.value_rows,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Rows<'a, T> {
+    type Item = TableRow<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        Some(TableRow {
+            table: self.table,
+            index,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Rows<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        Some(TableRow {
+            table: self.table,
+            index,
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Rows<'_, T> {}
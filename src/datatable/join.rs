@@ -0,0 +1,195 @@
+use std::cmp::Ordering;
+
+use super::{datatable_error::DataTableError, DataTable};
+use crate::numeric::Numeric;
+
+/// Defines how the rows of two tables are aligned by [`DataTable::join`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Each row is joined with the first row of the other table with the same base data value.
+    /// Rows without such a row are dropped.
+    #[default]
+    Exact,
+    /// Each row is joined with the row of the other table with the nearest base data value,
+    /// the earlier one on ties. Useful for inputs sampled at different times.
+    Nearest,
+}
+
+impl<T> DataTable<T>
+where
+    T: Numeric,
+{
+    /// Combines the columns of two tables, aligning their rows by base data value.
+    ///
+    /// The result keeps the rows and base data of `self` and appends the value and text columns
+    /// of `other`, except for its base data column. Rows with a missing base data value are dropped.
+    /// Fails with `DataTableError::DuplicateName` if both tables have a column with the same name.
+    pub fn join(
+        &self,
+        other: &DataTable<T>,
+        kind: JoinKind,
+    ) -> Result<DataTable<T>, DataTableError> {
+        let other_columns: Vec<usize> = (0..other.value_columns)
+            .filter(|i| Some(*i) != other.base_data_index)
+            .collect();
+        let mut names = self.value_names.clone();
+        names.extend(other_columns.iter().map(|i| other.value_names[*i].clone()));
+        let mut text_names = self.text_names.clone();
+        text_names.extend(other.text_names.iter().cloned());
+        for (i, name) in names.iter().chain(&text_names).enumerate() {
+            if names.iter().chain(&text_names).take(i).any(|n| n == name) {
+                return Err(DataTableError::DuplicateName(name.clone()));
+            }
+        }
+
+        let other_base = other.get_base_data();
+        let mut sorted: Vec<usize> = (0..other.value_rows)
+            .filter(|i| !other_base[*i].is_missing())
+            .collect();
+        sorted.sort_by(|a, b| {
+            other_base[*a]
+                .partial_cmp(&other_base[*b])
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let base = self.get_base_data();
+        let matches: Vec<(usize, usize)> = (0..self.value_rows)
+            .filter(|row| !base[*row].is_missing())
+            .filter_map(|row| {
+                let key = base[row];
+                let pos = sorted.partition_point(|i| other_base[*i] < key);
+                let matched = match kind {
+                    JoinKind::Exact => sorted.get(pos).filter(|i| other_base[**i] == key),
+                    JoinKind::Nearest => {
+                        let distance = |i: &usize| {
+                            let value = other_base[*i];
+                            if value > key {
+                                value - key
+                            } else {
+                                key - value
+                            }
+                        };
+                        let before = pos.checked_sub(1).and_then(|p| sorted.get(p));
+                        match (before, sorted.get(pos)) {
+                            (Some(b), Some(a)) if distance(a) < distance(b) => Some(a),
+                            (Some(b), _) => Some(b),
+                            (None, a) => a,
+                        }
+                    }
+                };
+                Some((row, *matched?))
+            })
+            .collect();
+
+        let mut table = DataTable::new(names.len(), Some(&names), self.base_data_index)
+            .with_text_columns(&text_names);
+        for (row, other_row) in &matches {
+            let values: Vec<_> = self
+                .value_data
+                .iter()
+                .map(|col| col[*row])
+                .chain(
+                    other_columns
+                        .iter()
+                        .map(|i| other.value_data[*i][*other_row]),
+                )
+                .collect();
+            let texts: Vec<_> = self
+                .text_data
+                .iter()
+                .map(|col| col[*row].clone())
+                .chain(other.text_data.iter().map(|col| col[*other_row].clone()))
+                .collect();
+            table.add_row(&values, &texts)?;
+        }
+        table.base_data = matches.iter().map(|(row, _)| base[*row]).collect();
+        table.metadata = self.metadata.clone();
+        let other_names = other_columns
+            .iter()
+            .map(|i| &other.value_names[*i])
+            .chain(&other.text_names);
+        for name in other_names {
+            if let Some(meta) = other.metadata.get(name) {
+                table.metadata.insert(name.clone(), meta.clone());
+            }
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatable::builder::DataTableBuilder;
+
+    fn table(columns: &[(&str, &[f64])]) -> DataTable<f64> {
+        let columns = columns
+            .iter()
+            .map(|(name, data)| (name.to_string(), data.to_vec()))
+            .collect();
+        DataTable::from_columns(columns, Some("time")).unwrap()
+    }
+
+    #[test]
+    fn exact_join_drops_rows_without_partner() {
+        let temps = table(&[("time", &[1.0, 2.0, 3.0]), ("temp", &[20.0, 21.0, 22.0])]);
+        let loads = table(&[("time", &[3.0, 1.0]), ("load", &[0.3, 0.1])]);
+        let joined = temps.join(&loads, JoinKind::Exact).unwrap();
+        assert_eq!(joined.col_slice_by_name("time").unwrap(), &[1.0, 3.0]);
+        assert_eq!(joined.col_slice_by_name("temp").unwrap(), &[20.0, 22.0]);
+        assert_eq!(joined.col_slice_by_name("load").unwrap(), &[0.1, 0.3]);
+    }
+
+    #[test]
+    fn nearest_join_prefers_the_earlier_row_on_ties() {
+        let temps = table(&[("time", &[1.0, 2.0, 10.0]), ("temp", &[20.0, 21.0, 22.0])]);
+        let loads = table(&[("time", &[0.0, 3.0, 4.0]), ("load", &[0.0, 0.3, 0.4])]);
+        let joined = temps.join(&loads, JoinKind::Nearest).unwrap();
+        assert_eq!(joined.col_slice_by_name("load").unwrap(), &[0.0, 0.3, 0.4]);
+    }
+
+    #[test]
+    fn join_rejects_duplicate_names() {
+        let temps = table(&[("time", &[1.0]), ("temp", &[20.0])]);
+        assert!(matches!(
+            temps.join(&temps, JoinKind::Exact),
+            Err(DataTableError::DuplicateName(name)) if name == "temp"
+        ));
+    }
+
+    #[test]
+    fn rows_with_missing_base_data_are_dropped() {
+        let temps = table(&[
+            ("time", &[1.0, f64::NAN, 2.0]),
+            ("temp", &[20.0, 21.0, 22.0]),
+        ]);
+        let loads = table(&[("time", &[f64::NAN, 2.0]), ("load", &[0.1, 0.2])]);
+        let joined = temps.join(&loads, JoinKind::Nearest).unwrap();
+        assert_eq!(joined.col_slice_by_name("temp").unwrap(), &[20.0, 22.0]);
+        assert_eq!(joined.col_slice_by_name("load").unwrap(), &[0.2, 0.2]);
+
+        let empty = table(&[("time", &[]), ("load", &[])]);
+        let joined = temps.join(&empty, JoinKind::Nearest).unwrap();
+        assert_eq!(joined.col_slice_by_name("load").unwrap(), &[] as &[f64]);
+    }
+
+    #[test]
+    fn text_columns_of_both_tables_are_joined() {
+        let mut levels = DataTableBuilder::<f64>::new(&["time".into()])
+            .unwrap()
+            .with_text_columns(&["level".into()])
+            .unwrap();
+        levels.add_row(&[1.0], &["INFO".into()]).unwrap();
+        levels.add_row(&[2.0], &["WARN".into()]).unwrap();
+        let levels = levels.build(Some("time")).unwrap();
+        let temps = table(&[("time", &[2.0, 3.0]), ("temp", &[20.0, 21.0])]);
+
+        let joined = temps.join(&levels, JoinKind::Nearest).unwrap();
+        let level: Vec<_> = joined.get_text_col_by_name("level").unwrap().collect();
+        assert_eq!(level, ["WARN", "WARN"]);
+        assert!(matches!(
+            levels.join(&levels, JoinKind::Exact),
+            Err(DataTableError::DuplicateName(name)) if name == "level"
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+use serde_json::{Map, Number, Value};
+
+use super::DataTable;
+use crate::numeric::Numeric;
+
+/// The structure of the JSON written by [`DataTable::to_json`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonLayout {
+    /// An array with one object per row, e.g. `[{"a": 1, "b": 2}, {"a": 3, "b": 4}]`.
+    #[default]
+    Records,
+    /// An object with one array per column, e.g. `{"a": [1, 3], "b": [2, 4]}`.
+    Columns,
+}
+
+impl<T> DataTable<T>
+where
+    T: Numeric,
+{
+    /// Converts the value columns followed by the text columns into JSON. Missing values become `null`.
+    pub fn to_json(&self, layout: JsonLayout) -> String {
+        self.json(layout, false)
+    }
+
+    /// Like [`DataTable::to_json`], but with the unit of the column metadata appended to the keys
+    /// like `temp [°C]`.
+    pub fn to_json_with_units(&self, layout: JsonLayout) -> String {
+        self.json(layout, true)
+    }
+
+    fn json(&self, layout: JsonLayout, units: bool) -> String {
+        let key = |name: &String| {
+            if units {
+                self.name_with_unit(name)
+            } else {
+                name.clone()
+            }
+        };
+        let json = match layout {
+            JsonLayout::Records => Value::Array(
+                (0..self.value_rows)
+                    .map(|row| {
+                        let values = self
+                            .value_names
+                            .iter()
+                            .zip(&self.value_data)
+                            .map(|(name, col)| (key(name), number(col[row])));
+                        let texts = self
+                            .text_names
+                            .iter()
+                            .zip(&self.text_data)
+                            .map(|(name, col)| (key(name), Value::from(col[row].as_str())));
+                        Value::Object(values.chain(texts).collect())
+                    })
+                    .collect(),
+            ),
+            JsonLayout::Columns => {
+                let values = self
+                    .value_names
+                    .iter()
+                    .zip(&self.value_data)
+                    .map(|(name, col)| (key(name), col.iter().copied().map(number).collect()));
+                let texts = self
+                    .text_names
+                    .iter()
+                    .zip(&self.text_data)
+                    .map(|(name, col)| (key(name), Value::from(col.clone())));
+                Value::Object(values.chain(texts).collect::<Map<_, _>>())
+            }
+        };
+        json.to_string()
+    }
+}
+
+/// Converts a value into a JSON number, keeping integers exact.
+fn number<T: Numeric>(value: T) -> Value {
+    let float = value.to_f64();
+    match value.to_i64() {
+        Some(int) if float == Some(int as f64) => Value::from(int),
+        _ => float
+            .and_then(Number::from_f64)
+            .map_or(Value::Null, Value::Number),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::datatable::{builder::DataTableBuilder, metadata::ColumnMeta};
+
+    fn table() -> DataTable<f64> {
+        let mut builder = DataTableBuilder::<f64>::new(&["time".into(), "temp".into()])
+            .unwrap()
+            .with_text_columns(&["level".into()])
+            .unwrap();
+        builder.add_row(&[1.0, 20.5], &["INFO".into()]).unwrap();
+        builder.add_row(&[2.0, f64::NAN], &["WARN".into()]).unwrap();
+        builder.build(Some("time")).unwrap()
+    }
+
+    #[test]
+    fn to_json_layouts() {
+        assert_eq!(
+            table().to_json(JsonLayout::Records),
+            r#"[{"time":1,"temp":20.5,"level":"INFO"},{"time":2,"temp":null,"level":"WARN"}]"#
+        );
+        assert_eq!(
+            table().to_json(JsonLayout::Columns),
+            r#"{"time":[1,2],"temp":[20.5,null],"level":["INFO","WARN"]}"#
+        );
+    }
+
+    #[test]
+    fn to_json_with_units_appends_the_unit() {
+        let mut table = table();
+        let meta = ColumnMeta {
+            unit: Some("°C".into()),
+            ..Default::default()
+        };
+        table.set_column_meta("temp", meta).unwrap();
+        assert_eq!(
+            table.to_json_with_units(JsonLayout::Columns),
+            r#"{"time":[1,2],"temp [°C]":[20.5,null],"level":["INFO","WARN"]}"#
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt::Display;
+
+use super::{display::AlignedColumns, DataTable};
+use crate::numeric::Numeric;
+
+/// Options for [`DataTable::to_markdown`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// The number of decimal places of floating point values, all significant ones if `None`.
+    pub precision: Option<usize>,
+    /// The maximum number of rows, all rows if `None`.
+    pub max_rows: Option<usize>,
+}
+
+impl<T> DataTable<T>
+where
+    T: Numeric + Display,
+{
+    /// Returns the value columns followed by the text columns as GitHub-flavored markdown table,
+    /// e.g. for pasting into issues. Value columns are right-aligned, text columns left-aligned.
+    ///
+    /// Rows beyond `max_rows` are summarized by a line below the table.
+    pub fn to_markdown(&self, options: MarkdownOptions) -> String {
+        let rows = options
+            .max_rows
+            .map_or(self.value_rows, |max| max.min(self.value_rows));
+        let value = |value: &T| match options.precision {
+            Some(precision) if !value.is_missing() => format!("{:.*}", precision, value),
+            _ => value.to_string(),
+        };
+        let escape = |text: &str| text.replace('|', "\\|").replace(['\n', '\r'], " ");
+        let columns = AlignedColumns::new(self, rows, value, escape, 3);
+
+        let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        let mut markdown = line(columns.header());
+        let separator = columns
+            .widths()
+            .map(|(width, right)| {
+                if right {
+                    format!("{}:", "-".repeat(width - 1))
+                } else {
+                    format!(":{}", "-".repeat(width - 1))
+                }
+            })
+            .collect();
+        markdown.push_str(&line(separator));
+        for row in 0..rows {
+            markdown.push_str(&line(columns.row(row)));
+        }
+        if self.value_rows > rows {
+            markdown.push_str(&format!("\n*{} more rows*\n", self.value_rows - rows));
+        }
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::datatable::builder::DataTableBuilder;
+
+    #[test]
+    fn to_markdown_aligns_and_escapes_the_cells() {
+        let mut builder = DataTableBuilder::<f64>::new(&["t".into(), "temp".into()])
+            .unwrap()
+            .with_text_columns(&["msg".into()])
+            .unwrap();
+        builder.add_row(&[1.0, 20.5], &["a|b".into()]).unwrap();
+        builder.add_row(&[2.0, 100.25], &["ok".into()]).unwrap();
+        builder.add_row(&[3.0, 7.0], &["x".into()]).unwrap();
+        let data = builder.build(None).unwrap();
+        let options = MarkdownOptions {
+            precision: Some(1),
+            max_rows: Some(2),
+        };
+        assert_eq!(
+            data.to_markdown(options),
+            "|   t |  temp | msg  |\n\
+             | --: | ----: | :--- |\n\
+             | 1.0 |  20.5 | a\\|b |\n\
+             | 2.0 | 100.2 | ok   |\n\
+             \n*1 more rows*\n"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use super::{datatable_error::DataTableError, DataTable};
+
+/// Describes the values of a column, e.g. for plot labels or CSV headers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnMeta {
+    /// The unit of the values like `°C` or `ms`.
+    pub unit: Option<String>,
+    pub description: Option<String>,
+    /// The pattern of the regex the column was extracted by.
+    pub source: Option<String>,
+}
+
+impl<T> DataTable<T> {
+    /// Returns the metadata of the value or text column `name`, if any was set.
+    pub fn column_meta(&self, name: &str) -> Option<&ColumnMeta> {
+        self.metadata.get(name)
+    }
+
+    /// Sets the metadata of the value or text column `name`, replacing any previous metadata.
+    pub fn set_column_meta(&mut self, name: &str, meta: ColumnMeta) -> Result<(), DataTableError> {
+        if !self
+            .value_names
+            .iter()
+            .chain(&self.text_names)
+            .any(|n| n == name)
+        {
+            return Err(DataTableError::InvalidColumnName(name.to_string()));
+        }
+        self.metadata.insert(name.to_string(), meta);
+        Ok(())
+    }
+
+    /// Returns the column name followed by its unit like `temp [°C]`, or only the name without unit.
+    pub(crate) fn name_with_unit(&self, name: &str) -> String {
+        match self.metadata.get(name).and_then(|m| m.unit.as_ref()) {
+            Some(unit) => format!("{} [{}]", name, unit),
+            None => name.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extract_data, NamedRegex};
+
+    #[test]
+    fn extracted_columns_carry_their_unit_and_source() {
+        let duration = NamedRegex::new_from_string("duration", r"took (\S+)")
+            .unwrap()
+            .with_unit("s");
+        let mut data: DataTable<f64> = extract_data(
+            &b"took 20ms\n"[..],
+            vec![duration],
+            vec![],
+            vec![],
+            None,
+            true,
+        )
+        .unwrap();
+        let meta = data.column_meta("duration").unwrap();
+        assert_eq!(meta.unit.as_deref(), Some("s"));
+        assert_eq!(meta.source.as_deref(), Some(r"took (\S+)"));
+
+        data.rename_column("duration", "took").unwrap();
+        assert_eq!(data.name_with_unit("took"), "took [s]");
+        data.set_column_meta("took", ColumnMeta::default()).unwrap();
+        assert_eq!(data.name_with_unit("took"), "took");
+        assert!(data
+            .set_column_meta("duration", ColumnMeta::default())
+            .is_err());
+    }
+}
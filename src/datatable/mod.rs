@@ -1,10 +1,38 @@
-use self::datatable_error::DataTableError;
+use std::{collections::HashMap, ops::Index};
 
-pub(crate) mod builder;
+pub use self::iter::{Rows, TableRow};
+pub use self::stats::ColumnStats;
+use self::{datatable_error::DataTableError, metadata::ColumnMeta};
+
+pub mod aggregation;
+#[cfg(feature = "arrow")]
+mod arrow;
+pub mod builder;
+mod compare;
+pub mod csv;
+#[cfg(feature = "polars")]
+mod dataframe;
 pub mod datatable_error;
+pub mod display;
+mod gnuplot;
+mod group;
 mod iter;
+pub mod join;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod markdown;
+pub mod metadata;
+mod ops;
+#[cfg(feature = "plot")]
+pub mod plot;
+pub mod prometheus;
+pub mod resample;
+mod sql;
+mod stats;
+pub mod time;
 
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DataTable<T> {
     value_columns: usize,
     value_rows: usize,
@@ -12,6 +40,10 @@ pub struct DataTable<T> {
     value_names: Vec<String>,
     value_data: Vec<Vec<T>>,
     base_data: Vec<T>,
+    text_names: Vec<String>,
+    text_data: Vec<Vec<String>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    metadata: HashMap<String, ColumnMeta>,
 }
 
 impl<T: Copy + num::Num> DataTable<T> {
@@ -32,9 +64,19 @@ impl<T: Copy + num::Num> DataTable<T> {
             value_names: names.unwrap_or(&a).to_vec(),
             value_data: vec![Vec::new(); columns],
             base_data: vec![],
+            text_names: vec![],
+            text_data: vec![],
+            metadata: HashMap::new(),
         }
     }
 
+    /// Adds text columns, which hold strings like labels or log levels next to the numeric values.
+    pub(crate) fn with_text_columns(mut self, names: &[String]) -> Self {
+        self.text_names = names.to_vec();
+        self.text_data = vec![Vec::new(); names.len()];
+        self
+    }
+
     pub fn new_with_base_data_index(
         columns: usize,
         names: Option<&[String]>,
@@ -43,7 +85,7 @@ impl<T: Copy + num::Num> DataTable<T> {
         if base_data_index < columns {
             Ok(Self::new(columns, names, base_data_index.into()))
         } else {
-            Err(DataTableError::InvalidCBaseDataIndex)
+            Err(DataTableError::InvalidCBaseDataIndex(base_data_index))
         }
     }
 
@@ -55,14 +97,103 @@ impl<T: Copy + num::Num> DataTable<T> {
         let index = names
             .iter()
             .position(|n| n == base_data_name)
-            .ok_or(DataTableError::InvalidCBaseDataName)?;
+            .ok_or_else(|| DataTableError::InvalidCBaseDataName(base_data_name.to_string()))?;
         Self::new_with_base_data_index(columns, Some(names), index)
     }
 
-    pub(crate) fn add_row(&mut self, data: &[T]) -> Result<(), DataTableError> {
-        if data.len() != self.value_columns {
-            return Err(DataTableError::InvalidColumnCount);
+    /// Creates a table from named columns of equal length, using the column `base_data_name`
+    /// as base data if given.
+    pub fn from_columns(
+        columns: Vec<(String, Vec<T>)>,
+        base_data_name: Option<&str>,
+    ) -> Result<Self, DataTableError> {
+        let rows = columns.first().map_or(0, |(_, c)| c.len());
+        for (i, (name, column)) in columns.iter().enumerate() {
+            if column.len() != rows {
+                return Err(DataTableError::InconsistentContainerSize(
+                    rows,
+                    column.len(),
+                ));
+            }
+            if columns[..i].iter().any(|(n, _)| n == name) {
+                return Err(DataTableError::DuplicateName(name.clone()));
+            }
+        }
+        let base_data_index = base_data_name
+            .map(|base| {
+                columns
+                    .iter()
+                    .position(|(n, _)| n == base)
+                    .ok_or_else(|| DataTableError::InvalidCBaseDataName(base.to_string()))
+            })
+            .transpose()?;
+        let base_data = match base_data_index {
+            Some(index) => columns[index].1.clone(),
+            None => std::iter::successors(Some(T::zero()), |prev| Some(*prev + T::one()))
+                .take(rows)
+                .collect(),
+        };
+        let (value_names, value_data): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+        Ok(DataTable {
+            value_columns: value_data.len(),
+            value_rows: rows,
+            base_data_index,
+            value_names,
+            value_data,
+            base_data,
+            text_names: vec![],
+            text_data: vec![],
+            metadata: HashMap::new(),
+        })
+    }
+
+    /// Creates a table with the named columns from rows holding one value per column,
+    /// using the column `base_data_name` as base data if given.
+    pub fn from_rows<R: AsRef<[T]>>(
+        names: &[String],
+        rows: impl IntoIterator<Item = R>,
+        base_data_name: Option<&str>,
+    ) -> Result<Self, DataTableError> {
+        let columns = names.iter().map(|n| (n.clone(), Vec::new())).collect();
+        let mut table = Self::from_columns(columns, base_data_name)?;
+        for row in rows {
+            table.add_row(row.as_ref(), &[])?;
         }
+        Ok(table)
+    }
+
+    /// Reserves space for at least `additional` more rows in every column.
+    pub fn reserve(&mut self, additional: usize) {
+        self.value_data
+            .iter_mut()
+            .for_each(|col| col.reserve(additional));
+        self.text_data
+            .iter_mut()
+            .for_each(|col| col.reserve(additional));
+        self.base_data.reserve(additional);
+    }
+
+    /// Appends a row with one value per value column and one text per text column, in column order,
+    /// e.g. to grow a table while a log is followed.
+    ///
+    /// The base data of the row is the value of the base data column, or the next row number
+    /// if the table has none.
+    pub fn push_row(&mut self, values: &[T], texts: &[String]) -> Result<(), DataTableError> {
+        self.add_row(values, texts)
+    }
+
+    pub(crate) fn add_row(&mut self, data: &[T], texts: &[String]) -> Result<(), DataTableError> {
+        if data.len() != self.value_columns || texts.len() != self.text_names.len() {
+            return Err(DataTableError::InvalidColumnCount {
+                expected: self.value_columns + self.text_names.len(),
+                actual: data.len() + texts.len(),
+            });
+        }
+
+        self.text_data
+            .iter_mut()
+            .zip(texts.iter())
+            .for_each(|a| a.0.push(a.1.clone()));
 
         self.value_data
             .iter_mut()
@@ -86,8 +217,30 @@ impl<T: Copy + num::Num> DataTable<T> {
     }
 
     pub fn get_col(&self, index: usize) -> Result<impl Iterator<Item = T> + '_, DataTableError> {
+        Ok(self.col_slice(index)?.iter().copied())
+    }
+
+    /// Returns the values of a column without copying them.
+    pub fn col_slice(&self, index: usize) -> Result<&[T], DataTableError> {
         self.check_column_index(index)?;
-        Ok(self.value_data[index].clone().into_iter())
+        Ok(&self.value_data[index])
+    }
+
+    /// Returns the value in the given row and column, or `None` if either is out of range.
+    ///
+    /// `table[(row, column)]` does the same but panics instead.
+    pub fn get(&self, row: usize, column: usize) -> Option<T> {
+        self.value_data.get(column)?.get(row).copied()
+    }
+
+    /// Returns the values of the named column without copying them.
+    pub fn col_slice_by_name(&self, name: &str) -> Result<&[T], DataTableError> {
+        self.col_slice(
+            self.value_names
+                .iter()
+                .position(|n| n == name)
+                .ok_or_else(|| DataTableError::InvalidColumnName(name.to_string()))?,
+        )
     }
     pub fn get_col_by_name(
         &self,
@@ -97,7 +250,7 @@ impl<T: Copy + num::Num> DataTable<T> {
             self.value_names
                 .iter()
                 .position(|n| n == name)
-                .ok_or(DataTableError::InvalidColumnName)?,
+                .ok_or_else(|| DataTableError::InvalidColumnName(name.to_string()))?,
         )
     }
 
@@ -116,12 +269,12 @@ impl<T: Copy + num::Num> DataTable<T> {
             self.value_names
                 .iter()
                 .position(|n| n == name)
-                .ok_or(DataTableError::InvalidColumnName)?,
+                .ok_or_else(|| DataTableError::InvalidColumnName(name.to_string()))?,
         )
     }
 
     pub fn get_row(&self, index: usize) -> Result<impl Iterator<Item = T> + '_, DataTableError> {
-        self.check_column_index(index)?;
+        self.check_row_index(index)?;
         let mut a: Vec<T> = Vec::new();
         a.push(self.get_base_data()[index]);
         for vd in self.value_data.iter() {
@@ -130,19 +283,224 @@ impl<T: Copy + num::Num> DataTable<T> {
         Ok(a.into_iter())
     }
 
+    #[deprecated(note = "use `iter`, which also gives access to the base data and text columns")]
     pub fn get_rows(&self) -> impl Iterator<Item = impl Iterator<Item = T> + '_> + '_ {
         (0..self.get_base_data().len()).map(|i| self.value_data.iter().map(move |vd| vd[i]))
     }
 
+    /// Returns an iterator over the rows of the table.
+    pub fn iter(&self) -> Rows<'_, T> {
+        Rows::new(self)
+    }
+
     pub fn get_names(&self) -> impl Iterator<Item = &String> + '_ {
         self.value_names.iter()
     }
 
+    pub fn get_text_names(&self) -> impl Iterator<Item = &String> + '_ {
+        self.text_names.iter()
+    }
+
+    pub fn get_text_col(
+        &self,
+        index: usize,
+    ) -> Result<impl Iterator<Item = &str> + '_, DataTableError> {
+        let col = self
+            .text_data
+            .get(index)
+            .ok_or(DataTableError::InvalidColumnIndex(index))?;
+        Ok(col.iter().map(String::as_str))
+    }
+
+    pub fn get_text_col_by_name(
+        &self,
+        name: &str,
+    ) -> Result<impl Iterator<Item = &str> + '_, DataTableError> {
+        self.get_text_col(
+            self.text_names
+                .iter()
+                .position(|n| n == name)
+                .ok_or_else(|| DataTableError::InvalidColumnName(name.to_string()))?,
+        )
+    }
+
+    pub fn get_text_row(
+        &self,
+        index: usize,
+    ) -> Result<impl Iterator<Item = &str> + '_, DataTableError> {
+        self.check_row_index(index)?;
+        Ok(self.text_data.iter().map(move |col| col[index].as_str()))
+    }
+
     fn check_column_index(&self, index: usize) -> Result<(), DataTableError> {
-        if index >= self.get_base_data().len() {
-            Err(DataTableError::InvalidColumnIndex)
+        if index >= self.value_columns {
+            Err(DataTableError::InvalidColumnIndex(index))
         } else {
             Ok(())
         }
     }
+
+    fn check_row_index(&self, index: usize) -> Result<(), DataTableError> {
+        if index >= self.value_rows {
+            Err(DataTableError::InvalidRowIndex(index))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<'a, T: Copy + num::Num> IntoIterator for &'a DataTable<T> {
+    type Item = TableRow<'a, T>;
+    type IntoIter = Rows<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Returns the values of the named column, like [`DataTable::col_slice_by_name`]
+/// but panicking if there is no such column.
+impl<T: Copy + num::Num> Index<&str> for DataTable<T> {
+    type Output = [T];
+
+    fn index(&self, name: &str) -> &[T] {
+        self.col_slice_by_name(name)
+            .unwrap_or_else(|_| panic!("there is no column named '{}'", name))
+    }
+}
+
+/// Returns the value in the given `(row, column)`, like [`DataTable::get`]
+/// but panicking if either is out of range.
+impl<T: Copy + num::Num> Index<(usize, usize)> for DataTable<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        &self.col_slice(column).unwrap_or_else(|e| panic!("{}", e))[row]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::datatable::builder::DataTableBuilder;
+
+    fn table() -> DataTable<f64> {
+        let mut builder = DataTableBuilder::<f64>::new(&["time".into(), "temp".into()])
+            .unwrap()
+            .with_text_columns(&["level".into()])
+            .unwrap();
+        builder.add_row(&[1.0, 20.5], &["INFO".into()]).unwrap();
+        builder.add_row(&[2.0, 21.0], &["WARN".into()]).unwrap();
+        builder.build(Some("time")).unwrap()
+    }
+
+    #[test]
+    fn rows_and_columns_are_accessed_by_index() {
+        let data = table();
+        assert_eq!(data.col_slice_by_name("temp").unwrap(), &[20.5, 21.0]);
+        assert_eq!(data.get(1, 1), Some(21.0));
+        assert_eq!(data[(0, 1)], 20.5);
+        assert_eq!(
+            data.get_row(1).unwrap().collect::<Vec<_>>(),
+            [2.0, 2.0, 21.0]
+        );
+        assert_eq!(data.get_text_row(0).unwrap().collect::<Vec<_>>(), ["INFO"]);
+    }
+
+    #[test]
+    fn out_of_range_indexes_are_errors() {
+        let data = table();
+        assert!(matches!(
+            data.col_slice(2),
+            Err(DataTableError::InvalidColumnIndex(2))
+        ));
+        assert!(matches!(
+            data.get_row(2),
+            Err(DataTableError::InvalidRowIndex(2))
+        ));
+        assert!(matches!(
+            data.get_text_row(5),
+            Err(DataTableError::InvalidRowIndex(5))
+        ));
+        assert_eq!(data.get(2, 0), None);
+    }
+
+    #[test]
+    fn tables_are_created_from_columns_and_rows() {
+        let names = ["time".to_string(), "temp".to_string()];
+        let rows = [[1.0, 20.5], [2.0, 21.0]];
+        let from_rows = DataTable::from_rows(&names, rows, Some("time")).unwrap();
+        let from_columns = DataTable::from_columns(
+            vec![
+                ("time".into(), vec![1.0, 2.0]),
+                ("temp".into(), vec![20.5, 21.0]),
+            ],
+            Some("time"),
+        )
+        .unwrap();
+        assert!(from_rows.approx_eq(&from_columns, 0.0));
+
+        let unbased = DataTable::<f64>::from_rows(&names, rows, None).unwrap();
+        assert_eq!(unbased.get_base_data(), &[0.0, 1.0]);
+        assert!(matches!(
+            DataTable::<f64>::from_columns(
+                vec![("a".into(), vec![1.0]), ("b".into(), vec![])],
+                None
+            ),
+            Err(DataTableError::InconsistentContainerSize(1, 0))
+        ));
+        assert!(matches!(
+            DataTable::from_rows(&names, [[1.0]], None),
+            Err(DataTableError::InvalidColumnCount {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        assert!(DataTable::from_rows(&names, rows, Some("pressure")).is_err());
+    }
+
+    #[test]
+    fn pushed_rows_continue_the_base_data() {
+        let mut data = table();
+        data.push_row(&[3.0, 22.0], &["ERROR".into()]).unwrap();
+        assert_eq!(data.get_base_data(), &[1.0, 2.0, 3.0]);
+        assert!(data.push_row(&[4.0, 23.0], &[]).is_err());
+        assert_eq!(data.iter().len(), 3);
+
+        let mut unbased =
+            DataTable::<f64>::from_columns(vec![("temp".into(), vec![])], None).unwrap();
+        unbased.push_row(&[20.0], &[]).unwrap();
+        unbased.push_row(&[21.0], &[]).unwrap();
+        assert_eq!(unbased.get_base_data(), &[0.0, 1.0]);
+    }
+
+    #[test]
+    fn rows_are_iterated_in_both_directions() {
+        let data = table();
+        let mut rows = data.iter();
+        assert_eq!(rows.len(), 2);
+        let last = rows.next_back().unwrap();
+        assert_eq!((last.index(), last.base()), (1, 2.0));
+        assert_eq!(last.get("temp"), Some(21.0));
+        assert_eq!(last.get_text("level"), Some("WARN"));
+        assert_eq!(last.get("pressure"), None);
+        let first = rows.next().unwrap();
+        assert_eq!(first.values().collect::<Vec<_>>(), [1.0, 20.5]);
+        assert_eq!(first.texts().collect::<Vec<_>>(), ["INFO"]);
+        assert!(rows.next().is_none());
+    }
+
+    #[test]
+    fn columns_are_indexed_by_name() {
+        let data = table();
+        assert_eq!(&data["temp"], &[20.5, 21.0]);
+        assert_eq!(data[(1, 0)], 2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no column named 'pressure'")]
+    fn indexing_unknown_columns_panics() {
+        let _ = &table()["pressure"];
+    }
 }
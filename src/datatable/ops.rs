@@ -0,0 +1,557 @@
+use std::cmp::Ordering;
+
+use super::{datatable_error::DataTableError, DataTable, TableRow};
+use crate::numeric::Numeric;
+
+impl<T> DataTable<T>
+where
+    T: Numeric,
+{
+    /// Reorders all rows, including the base data and the text columns, by the values of the named column.
+    ///
+    /// The sort is stable and missing values are placed last in both directions.
+    pub fn sort_by_column(&mut self, name: &str, ascending: bool) -> Result<(), DataTableError> {
+        let index = self
+            .value_names
+            .iter()
+            .position(|n| n == name)
+            .ok_or_else(|| DataTableError::InvalidColumnName(name.to_string()))?;
+
+        let key = &self.value_data[index];
+        let mut order: Vec<usize> = (0..self.value_rows).collect();
+        order.sort_by(|a, b| {
+            let (a, b) = (key[*a], key[*b]);
+            match (a.is_missing(), b.is_missing()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+                    if ascending {
+                        ordering
+                    } else {
+                        ordering.reverse()
+                    }
+                }
+            }
+        });
+
+        for col in &mut self.value_data {
+            reorder(col, &order);
+        }
+        for col in &mut self.text_data {
+            reorder(col, &order);
+        }
+        reorder(&mut self.base_data, &order);
+        Ok(())
+    }
+
+    /// Appends a value column computed from the other columns of each row,
+    /// e.g. `|row| row.get("voltage").zip(row.get("current")).map_or(f64::NAN, |(u, i)| u * i)`.
+    pub fn add_computed_column(
+        &mut self,
+        name: &str,
+        compute: impl FnMut(TableRow<'_, T>) -> T,
+    ) -> Result<(), DataTableError> {
+        self.check_new_name(name)?;
+        let col = self.iter().map(compute).collect();
+        self.push_column(name, col);
+        Ok(())
+    }
+
+    /// Appends a value column with the derivative of the value column `column` with respect
+    /// to the base data, e.g. to turn a cumulative filament length into a flow rate.
+    ///
+    /// Uses central differences, and one-sided differences for the first and the last row.
+    /// Fails with `DataTableError::MissingValue` if `T` cannot hold a derivative which is not a number,
+    /// e.g. for integer tables with repeated base data.
+    pub fn derive(&mut self, column: &str, name: &str) -> Result<(), DataTableError> {
+        self.check_new_name(name)?;
+        let (base, values) = self.base_and_values(column)?;
+        let last = values.len().saturating_sub(1);
+        let col = (0..values.len())
+            .map(|i| {
+                let (before, after) = (i.saturating_sub(1), (i + 1).min(last));
+                (values[after] - values[before]) / (base[after] - base[before])
+            })
+            .map(|value| from_f64(value, name))
+            .collect::<Result<_, _>>()?;
+        self.push_column(name, col);
+        Ok(())
+    }
+
+    /// Appends a value column with the integral of the value column `column` over the base data
+    /// from the first row on, using the trapezoid rule.
+    /// Fails with `DataTableError::MissingValue` like [`DataTable::derive`].
+    pub fn integrate(&mut self, column: &str, name: &str) -> Result<(), DataTableError> {
+        self.check_new_name(name)?;
+        let (base, values) = self.base_and_values(column)?;
+        let col = (0..values.len())
+            .scan(0.0, |sum, i| {
+                if i > 0 {
+                    *sum += (values[i] + values[i - 1]) / 2.0 * (base[i] - base[i - 1]);
+                }
+                Some(*sum)
+            })
+            .map(|value| from_f64(value, name))
+            .collect::<Result<_, _>>()?;
+        self.push_column(name, col);
+        Ok(())
+    }
+
+    /// Appends the rows of `other`, which needs the same columns and base data column,
+    /// e.g. for rotated log files processed one after another.
+    ///
+    /// `base_offset` is added to the base data of `other`, e.g. the number of rows of `self`
+    /// to continue the row numbers of tables without base data column.
+    pub fn append(
+        &mut self,
+        mut other: DataTable<T>,
+        base_offset: Option<T>,
+    ) -> Result<(), DataTableError> {
+        let (names, other_names) = (
+            self.value_names.iter().chain(&self.text_names),
+            other.value_names.iter().chain(&other.text_names),
+        );
+        if names.clone().count() != other_names.clone().count() {
+            return Err(DataTableError::InvalidColumnCount {
+                expected: names.count(),
+                actual: other_names.count(),
+            });
+        }
+        if let Some((_, name)) = names.zip(other_names).find(|(a, b)| a != b) {
+            return Err(DataTableError::InvalidColumnName(name.clone()));
+        }
+        if self.base_data_index != other.base_data_index {
+            return Err(DataTableError::InconsistentBaseData);
+        }
+
+        if let Some(offset) = base_offset {
+            if let Some(index) = other.base_data_index {
+                other.value_data[index]
+                    .iter_mut()
+                    .for_each(|v| *v = *v + offset);
+            }
+            other.base_data.iter_mut().for_each(|v| *v = *v + offset);
+        }
+        for (col, other) in self.value_data.iter_mut().zip(other.value_data) {
+            col.extend(other);
+        }
+        for (col, other) in self.text_data.iter_mut().zip(other.text_data) {
+            col.extend(other);
+        }
+        self.base_data.extend(other.base_data);
+        self.value_rows += other.value_rows;
+        Ok(())
+    }
+
+    /// Removes all rows with a missing value in any value column.
+    pub fn drop_nan_rows(&mut self) {
+        let rows: Vec<usize> = (0..self.value_rows)
+            .filter(|i| !self.value_data.iter().any(|col| col[*i].is_missing()))
+            .collect();
+        for col in &mut self.value_data {
+            reorder(col, &rows);
+        }
+        for col in &mut self.text_data {
+            reorder(col, &rows);
+        }
+        reorder(&mut self.base_data, &rows);
+        self.value_rows = rows.len();
+    }
+
+    /// Replaces all missing values by `value`.
+    pub fn fill_nan(&mut self, value: T) {
+        for v in self.value_data.iter_mut().flatten() {
+            if v.is_missing() {
+                *v = value;
+            }
+        }
+        if self.base_data_index.is_some() {
+            self.base_data = self.get_base_data().clone();
+        }
+    }
+
+    /// Replaces all missing values by the last value before them in the same column.
+    /// Missing values at the start of a column are kept.
+    pub fn forward_fill(&mut self) {
+        for col in &mut self.value_data {
+            let mut last = None;
+            for v in col.iter_mut() {
+                if !v.is_missing() {
+                    last = Some(*v);
+                } else if let Some(last) = last {
+                    *v = last;
+                }
+            }
+        }
+        if self.base_data_index.is_some() {
+            self.base_data = self.get_base_data().clone();
+        }
+    }
+
+    /// Renames the value or text column `name` to `new_name`.
+    pub fn rename_column(&mut self, name: &str, new_name: &str) -> Result<(), DataTableError> {
+        if name != new_name
+            && self
+                .value_names
+                .iter()
+                .chain(&self.text_names)
+                .any(|n| n == new_name)
+        {
+            return Err(DataTableError::DuplicateName(new_name.to_string()));
+        }
+        let column = self
+            .value_names
+            .iter_mut()
+            .chain(&mut self.text_names)
+            .find(|n| *n == name)
+            .ok_or_else(|| DataTableError::InvalidColumnName(name.to_string()))?;
+        *column = new_name.to_string();
+        if let Some(meta) = self.metadata.remove(name) {
+            self.metadata.insert(new_name.to_string(), meta);
+        }
+        Ok(())
+    }
+
+    /// Removes the value or text column `name`. The base data is kept when removing the base data column.
+    pub fn drop_column(&mut self, name: &str) -> Result<(), DataTableError> {
+        let names: Vec<_> = self
+            .value_names
+            .iter()
+            .chain(&self.text_names)
+            .filter(|n| *n != name)
+            .cloned()
+            .collect();
+        if names.len() == self.value_names.len() + self.text_names.len() {
+            return Err(DataTableError::InvalidColumnName(name.to_string()));
+        }
+        let names: Vec<_> = names.iter().map(String::as_str).collect();
+        self.select_columns(&names)
+    }
+
+    /// Keeps only the named value and text columns, in the given order.
+    /// The base data is kept when the base data column is not selected.
+    pub fn select_columns(&mut self, names: &[&str]) -> Result<(), DataTableError> {
+        let mut values = vec![];
+        let mut texts = vec![];
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].contains(name) {
+                return Err(DataTableError::DuplicateName(name.to_string()));
+            }
+            if let Some(index) = self.value_names.iter().position(|n| n == name) {
+                values.push(index);
+            } else if let Some(index) = self.text_names.iter().position(|n| n == name) {
+                texts.push(index);
+            } else {
+                return Err(DataTableError::InvalidColumnName(name.to_string()));
+            }
+        }
+
+        self.base_data = self.get_base_data().clone();
+        self.base_data_index = self
+            .base_data_index
+            .and_then(|base| values.iter().position(|i| *i == base));
+        self.value_names = values
+            .iter()
+            .map(|i| self.value_names[*i].clone())
+            .collect();
+        self.value_data = values.iter().map(|i| self.value_data[*i].clone()).collect();
+        self.value_columns = values.len();
+        self.text_names = texts.iter().map(|i| self.text_names[*i].clone()).collect();
+        self.text_data = texts.iter().map(|i| self.text_data[*i].clone()).collect();
+        self.metadata
+            .retain(|name, _| names.contains(&name.as_str()));
+        Ok(())
+    }
+
+    /// Appends a value column with the running sum of the value column `column`,
+    /// e.g. to turn bytes per line into the total over time. Missing values stay missing and are skipped.
+    pub fn cumsum(&mut self, column: &str, name: &str) -> Result<(), DataTableError> {
+        self.accumulate(column, name, |sum, value| sum + value)
+    }
+
+    /// Appends a value column with the running maximum of the value column `column`.
+    /// Missing values stay missing and are skipped.
+    pub fn cummax(&mut self, column: &str, name: &str) -> Result<(), DataTableError> {
+        self.accumulate(
+            column,
+            name,
+            |max, value| if value > max { value } else { max },
+        )
+    }
+
+    fn accumulate(
+        &mut self,
+        column: &str,
+        name: &str,
+        step: impl Fn(T, T) -> T,
+    ) -> Result<(), DataTableError> {
+        self.check_new_name(name)?;
+        let mut acc = None;
+        let col = self
+            .col_slice_by_name(column)?
+            .iter()
+            .map(|value| {
+                if value.is_missing() {
+                    return *value;
+                }
+                let next = acc.map_or(*value, |acc| step(acc, *value));
+                acc = Some(next);
+                next
+            })
+            .collect();
+        self.push_column(name, col);
+        Ok(())
+    }
+
+    /// Returns a table with the rows whose base data is between `base_min` and `base_max`, both included,
+    /// e.g. to focus on a time window. Rows with missing base data are dropped.
+    pub fn between(&self, base_min: T, base_max: T) -> DataTable<T> {
+        let base = self.get_base_data();
+        let rows: Vec<usize> = (0..self.value_rows)
+            .filter(|i| base[*i] >= base_min && base[*i] <= base_max)
+            .collect();
+        self.take_rows(&rows)
+    }
+
+    /// Returns a table with the given rows of this table, in the given order.
+    pub(crate) fn take_rows(&self, rows: &[usize]) -> DataTable<T> {
+        let take = |col: &Vec<T>| rows.iter().map(|i| col[*i]).collect();
+        DataTable {
+            value_columns: self.value_columns,
+            value_rows: rows.len(),
+            base_data_index: self.base_data_index,
+            value_names: self.value_names.clone(),
+            value_data: self.value_data.iter().map(take).collect(),
+            base_data: take(&self.base_data),
+            text_names: self.text_names.clone(),
+            text_data: self
+                .text_data
+                .iter()
+                .map(|col| rows.iter().map(|i| col[*i].clone()).collect())
+                .collect(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    fn check_new_name(&self, name: &str) -> Result<(), DataTableError> {
+        if self
+            .value_names
+            .iter()
+            .chain(&self.text_names)
+            .any(|n| n == name)
+        {
+            return Err(DataTableError::DuplicateName(name.to_string()));
+        }
+        Ok(())
+    }
+
+    fn push_column(&mut self, name: &str, data: Vec<T>) {
+        self.value_names.push(name.to_string());
+        self.value_data.push(data);
+        self.value_columns += 1;
+    }
+
+    /// Returns the base data and the values of the value column `column` as `f64`, missing values as NaN.
+    fn base_and_values(&self, column: &str) -> Result<(Vec<f64>, Vec<f64>), DataTableError> {
+        let float = |v: &T| v.to_f64().filter(|_| !v.is_missing()).unwrap_or(f64::NAN);
+        let values = self.col_slice_by_name(column)?.iter().map(float).collect();
+        Ok((self.get_base_data().iter().map(float).collect(), values))
+    }
+}
+
+/// Rearranges `values` so that the value at `order[i]` moves to `i`.
+fn reorder<V: Clone>(values: &mut Vec<V>, order: &[usize]) {
+    *values = order.iter().map(|i| values[*i].clone()).collect();
+}
+
+/// Converts a value computed for the column `name` into `T`, falling back to a missing value
+/// if `T` cannot represent it. Fails for types without missing values, e.g. NaN for integer types.
+fn from_f64<T: Numeric>(value: f64, name: &str) -> Result<T, DataTableError> {
+    T::from(value)
+        .or_else(T::missing)
+        .ok_or_else(|| DataTableError::MissingValue(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table<T: Numeric>(columns: &[(&str, &[T])]) -> DataTable<T> {
+        let columns = columns
+            .iter()
+            .map(|(name, data)| (name.to_string(), data.to_vec()))
+            .collect();
+        DataTable::from_columns(columns, Some("time")).unwrap()
+    }
+
+    #[test]
+    fn add_computed_column_gets_the_rows() {
+        let mut data = table(&[
+            ("time", &[1.0, 2.0]),
+            ("u", &[5.0, 6.0]),
+            ("i", &[2.0, 0.5]),
+        ]);
+        data.add_computed_column("p", |row| row.get("u").unwrap() * row.get("i").unwrap())
+            .unwrap();
+        assert_eq!(data.col_slice_by_name("p").unwrap(), &[10.0, 3.0]);
+        assert!(data.add_computed_column("u", |row| row.base()).is_err());
+    }
+
+    #[test]
+    fn derive_and_integrate() {
+        let mut data = table(&[("time", &[0.0, 1.0, 3.0]), ("length", &[0.0, 2.0, 6.0])]);
+        data.derive("length", "flow").unwrap();
+        data.integrate("flow", "integral").unwrap();
+        assert_eq!(data.col_slice_by_name("flow").unwrap(), &[2.0, 2.0, 2.0]);
+        assert_eq!(
+            data.col_slice_by_name("integral").unwrap(),
+            &[0.0, 2.0, 6.0]
+        );
+    }
+
+    #[test]
+    fn integer_tables_reject_derivatives_which_are_no_number() {
+        let mut data = table::<i64>(&[("time", &[0, 2, 4]), ("count", &[0, 4, 12])]);
+        data.derive("count", "rate").unwrap();
+        assert_eq!(data.col_slice_by_name("rate").unwrap(), &[2, 3, 4]);
+
+        let mut repeated = table::<i64>(&[("time", &[1, 1]), ("count", &[0, 4])]);
+        assert!(matches!(
+            repeated.derive("count", "rate"),
+            Err(DataTableError::MissingValue(name)) if name == "rate"
+        ));
+        assert!(repeated.get_col_by_name("rate").is_err());
+    }
+
+    #[test]
+    fn sorting_places_missing_values_last() {
+        let mut data = table(&[
+            ("time", &[1.0, 2.0, 3.0, 4.0]),
+            ("temp", &[20.0, f64::NAN, 10.0, 20.0]),
+        ]);
+        data.sort_by_column("temp", false).unwrap();
+        assert_eq!(
+            data.col_slice_by_name("time").unwrap(),
+            &[1.0, 4.0, 3.0, 2.0]
+        );
+        assert_eq!(data.get_base_data(), &[1.0, 4.0, 3.0, 2.0]);
+        data.sort_by_column("temp", true).unwrap();
+        assert_eq!(
+            data.col_slice_by_name("time").unwrap(),
+            &[3.0, 1.0, 4.0, 2.0]
+        );
+        assert!(data.sort_by_column("pressure", true).is_err());
+    }
+
+    #[test]
+    fn append_checks_the_columns_and_offsets_the_base_data() {
+        let mut data = table(&[("time", &[0.0, 1.0]), ("temp", &[20.0, 21.0])]);
+        let rotated = table(&[("time", &[0.0]), ("temp", &[22.0])]);
+        data.append(rotated, Some(2.0)).unwrap();
+        assert_eq!(data.col_slice_by_name("time").unwrap(), &[0.0, 1.0, 2.0]);
+        assert_eq!(data.get_base_data(), &[0.0, 1.0, 2.0]);
+        assert_eq!(data.col_slice_by_name("temp").unwrap(), &[20.0, 21.0, 22.0]);
+
+        let other = table(&[("time", &[0.0]), ("load", &[0.5])]);
+        assert!(matches!(
+            data.append(other, None),
+            Err(DataTableError::InvalidColumnName(name)) if name == "load"
+        ));
+        let wider = table(&[("time", &[0.0]), ("temp", &[1.0]), ("load", &[0.5])]);
+        assert!(matches!(
+            data.append(wider, None),
+            Err(DataTableError::InvalidColumnCount {
+                expected: 2,
+                actual: 3
+            })
+        ));
+        let unbased = DataTable::from_columns(
+            vec![("time".into(), vec![0.0]), ("temp".into(), vec![1.0])],
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            data.append(unbased, None),
+            Err(DataTableError::InconsistentBaseData)
+        ));
+    }
+
+    #[test]
+    fn missing_values_are_dropped_or_filled() {
+        let nan = f64::NAN;
+        let data = || table(&[("time", &[1.0, nan, 3.0]), ("temp", &[nan, 2.0, 3.0])]);
+
+        let mut dropped = data();
+        dropped.drop_nan_rows();
+        assert_eq!(dropped.col_slice_by_name("temp").unwrap(), &[3.0]);
+        assert_eq!(dropped.get_base_data(), &[3.0]);
+
+        let mut filled = data();
+        filled.fill_nan(0.0);
+        assert_eq!(filled.col_slice_by_name("temp").unwrap(), &[0.0, 2.0, 3.0]);
+        assert_eq!(filled.get_base_data(), &[1.0, 0.0, 3.0]);
+
+        let mut forward = data();
+        forward.forward_fill();
+        assert!(forward.col_slice_by_name("temp").unwrap()[0].is_nan());
+        assert_eq!(forward.get_base_data(), &[1.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn columns_are_renamed_dropped_and_selected() {
+        let mut data = table(&[
+            ("time", &[1.0, 2.0]),
+            ("temp", &[20.0, 21.0]),
+            ("load", &[0.1, 0.2]),
+        ]);
+        assert!(matches!(
+            data.rename_column("temp", "load"),
+            Err(DataTableError::DuplicateName(name)) if name == "load"
+        ));
+        data.rename_column("temp", "temperature").unwrap();
+        assert!(data.rename_column("temp", "t").is_err());
+
+        data.drop_column("time").unwrap();
+        assert_eq!(data.get_base_data(), &[1.0, 2.0]);
+        assert!(data.drop_column("time").is_err());
+
+        data.select_columns(&["load", "temperature"]).unwrap();
+        let names: Vec<_> = data.get_names().collect();
+        assert_eq!(names, ["load", "temperature"]);
+        assert_eq!(data.get_base_data(), &[1.0, 2.0]);
+        assert!(matches!(
+            data.select_columns(&["load", "load"]),
+            Err(DataTableError::DuplicateName(_))
+        ));
+    }
+
+    #[test]
+    fn running_totals_skip_missing_values() {
+        let mut data = table(&[
+            ("time", &[1.0, 2.0, 3.0, 4.0]),
+            ("bytes", &[3.0, f64::NAN, 1.0, 5.0]),
+        ]);
+        data.cumsum("bytes", "total").unwrap();
+        data.cummax("bytes", "peak").unwrap();
+        let total = data.col_slice_by_name("total").unwrap();
+        assert!(total[1].is_nan());
+        assert_eq!([total[0], total[2], total[3]], [3.0, 4.0, 9.0]);
+        let peak = data.col_slice_by_name("peak").unwrap();
+        assert_eq!([peak[0], peak[2], peak[3]], [3.0, 3.0, 5.0]);
+        assert!(data.cumsum("bytes", "total").is_err());
+    }
+
+    #[test]
+    fn between_includes_both_ends() {
+        let data = table(&[
+            ("time", &[1.0, 2.0, f64::NAN, 3.0, 4.0]),
+            ("temp", &[10.0, 20.0, 25.0, 30.0, 40.0]),
+        ]);
+        let window = data.between(2.0, 3.0);
+        assert_eq!(window.col_slice_by_name("temp").unwrap(), &[20.0, 30.0]);
+        assert_eq!(window.get_base_data(), &[2.0, 3.0]);
+        assert_eq!(data.between(5.0, 6.0).iter().len(), 0);
+    }
+}
@@ -0,0 +1,171 @@
+use std::{io, ops::Range, path::Path};
+
+use plotters::{coord::Shift, prelude::*};
+
+use super::DataTable;
+use crate::numeric::Numeric;
+
+/// Options for [`DataTable::plot_svg`] and [`DataTable::plot_png`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotOptions {
+    pub title: Option<String>,
+    /// The label of the base data axis.
+    pub x_label: Option<String>,
+    pub y_label: Option<String>,
+    /// The size of the image in pixels.
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        Self {
+            title: None,
+            x_label: None,
+            y_label: None,
+            width: 800,
+            height: 600,
+        }
+    }
+}
+
+impl<T: Numeric> DataTable<T> {
+    /// Renders the value columns `columns` as line chart against the base data into an SVG file.
+    ///
+    /// Missing values interrupt the lines. Fails with `io::ErrorKind::InvalidInput` for unknown columns.
+    pub fn plot_svg(
+        &self,
+        path: impl AsRef<Path>,
+        columns: &[&str],
+        options: PlotOptions,
+    ) -> io::Result<()> {
+        let size = (options.width, options.height);
+        let root = SVGBackend::new(path.as_ref(), size).into_drawing_area();
+        self.draw(root, columns, options)
+    }
+
+    /// Like [`DataTable::plot_svg`], but renders a PNG file.
+    pub fn plot_png(
+        &self,
+        path: impl AsRef<Path>,
+        columns: &[&str],
+        options: PlotOptions,
+    ) -> io::Result<()> {
+        let size = (options.width, options.height);
+        let root = BitMapBackend::new(path.as_ref(), size).into_drawing_area();
+        self.draw(root, columns, options)
+    }
+
+    /// Like [`DataTable::plot_svg`], but writes the SVG into `writer`.
+    pub fn write_svg(
+        &self,
+        writer: &mut impl io::Write,
+        columns: &[&str],
+        options: PlotOptions,
+    ) -> io::Result<()> {
+        let mut svg = String::new();
+        {
+            let size = (options.width, options.height);
+            let root = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+            self.draw(root, columns, options)?;
+        }
+        writer.write_all(svg.as_bytes())
+    }
+
+    fn draw<DB>(
+        &self,
+        root: DrawingArea<DB, Shift>,
+        columns: &[&str],
+        options: PlotOptions,
+    ) -> io::Result<()>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        let float = |v: &T| v.to_f64().filter(|_| !v.is_missing());
+        let base: Vec<_> = self.get_base_data().iter().map(float).collect();
+        // Missing values end the current line, the next value starts a new one.
+        let series = columns
+            .iter()
+            .map(|name| {
+                let col = self
+                    .col_slice_by_name(name)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                let mut lines: Vec<Vec<(f64, f64)>> = vec![vec![]];
+                for point in base.iter().zip(col).map(|(x, y)| x.zip(float(y))) {
+                    match (point, lines.last_mut()) {
+                        (Some(point), Some(line)) => line.push(point),
+                        _ => lines.push(vec![]),
+                    }
+                }
+                lines.retain(|line| !line.is_empty());
+                Ok(lines)
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let points = || series.iter().flatten().flatten();
+        let x_range = range(points().map(|(x, _)| *x));
+        let y_range = range(points().map(|(_, y)| *y));
+
+        root.fill(&WHITE).map_err(plot_error)?;
+        let mut builder = ChartBuilder::on(&root);
+        builder
+            .margin(20)
+            .x_label_area_size(40)
+            .y_label_area_size(60);
+        if let Some(title) = &options.title {
+            builder.caption(title, ("sans-serif", 20));
+        }
+        let mut chart = builder
+            .build_cartesian_2d(x_range, y_range)
+            .map_err(plot_error)?;
+        let mut mesh = chart.configure_mesh();
+        if let Some(label) = &options.x_label {
+            mesh.x_desc(label);
+        }
+        if let Some(label) = &options.y_label {
+            mesh.y_desc(label);
+        }
+        mesh.draw().map_err(plot_error)?;
+
+        for (i, (name, lines)) in columns.iter().zip(&series).enumerate() {
+            let color = Palette99::pick(i).stroke_width(2);
+            for (j, line) in lines.iter().enumerate() {
+                let drawn = chart
+                    .draw_series(LineSeries::new(line.iter().copied(), color))
+                    .map_err(plot_error)?;
+                // Only the first part of each line gets an entry in the legend.
+                if j == 0 {
+                    drawn
+                        .label(*name)
+                        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+                }
+            }
+        }
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()
+            .map_err(plot_error)?;
+        root.present().map_err(plot_error)
+    }
+}
+
+/// Returns the range from the smallest to the largest value, widened if they are equal or there are no values.
+fn range(values: impl Iterator<Item = f64>) -> Range<f64> {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    if min > max {
+        0.0..1.0
+    } else if min == max {
+        min - 0.5..max + 0.5
+    } else {
+        min..max
+    }
+}
+
+fn plot_error<E: std::error::Error + Send + Sync + 'static>(e: E) -> io::Error {
+    io::Error::other(e)
+}
@@ -0,0 +1,163 @@
+use std::{collections::HashMap, fmt::Display, io};
+
+use super::{aggregation::Aggregation, DataTable};
+use crate::numeric::Numeric;
+
+/// Options for [`DataTable::write_prometheus`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrometheusOptions {
+    /// Prepended to the metric names, e.g. `printer_`.
+    pub prefix: String,
+    /// Metric names replacing the column names, by column name.
+    pub names: HashMap<String, String>,
+    /// Labels added to every metric, like `("host", "printer-1")`.
+    pub labels: Vec<(String, String)>,
+    /// Reduces each column to its metric value, the last value of the column if `None`.
+    pub aggregation: Option<Aggregation>,
+}
+
+impl<T> DataTable<T>
+where
+    T: Numeric + Display,
+{
+    /// Writes one gauge per value column in the Prometheus text exposition format, e.g. to serve
+    /// the latest extracted values to a Prometheus scraper.
+    ///
+    /// Missing values are ignored, columns without value are skipped.
+    /// Characters not allowed in metric names are replaced by `_`. Fails with `io::ErrorKind::InvalidInput`
+    /// before writing anything if two columns get the same metric name, like `temp.a` and `temp_a`.
+    pub fn write_prometheus(
+        &self,
+        writer: &mut impl io::Write,
+        options: &PrometheusOptions,
+    ) -> io::Result<()> {
+        let labels: Vec<_> = options
+            .labels
+            .iter()
+            .map(|(name, value)| {
+                let name = metric_name(name).replace(':', "_");
+                format!("{}=\"{}\"", name, label_value(value))
+            })
+            .collect();
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", labels.join(","))
+        };
+
+        let names: Vec<_> = self
+            .value_names
+            .iter()
+            .map(|column| {
+                metric_name(&format!(
+                    "{}{}",
+                    options.prefix,
+                    options.names.get(column).unwrap_or(column)
+                ))
+            })
+            .collect();
+        let mut columns = HashMap::new();
+        for (column, name) in self.value_names.iter().zip(&names) {
+            if let Some(other) = columns.insert(name, column) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "the columns '{}' and '{}' are both written as metric '{}'",
+                        other, column, name
+                    ),
+                ));
+            }
+        }
+
+        for (data, name) in self.value_data.iter().zip(&names) {
+            let values = data.iter().copied().filter(|v| !v.is_missing());
+            let value = match options.aggregation {
+                Some(aggregation) => aggregation.apply(values),
+                None => values.last(),
+            };
+            let Some(value) = value else {
+                continue;
+            };
+            writeln!(writer, "# TYPE {} gauge", name)?;
+            let value = match value.to_f64() {
+                Some(f64::INFINITY) => "+Inf".to_string(),
+                Some(f64::NEG_INFINITY) => "-Inf".to_string(),
+                _ => value.to_string(),
+            };
+            writeln!(writer, "{}{} {}", name, labels, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Replaces characters which are not allowed in metric and label names by `_`.
+fn metric_name(name: &str) -> String {
+    name.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if c.is_ascii_alphabetic() || c == '_' || c == ':' || (i > 0 && c.is_ascii_digit()) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatable::builder::DataTableBuilder;
+
+    fn table(names: &[&str], rows: &[&[f64]]) -> DataTable<f64> {
+        let names: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+        let mut builder = DataTableBuilder::new(&names).unwrap();
+        for row in rows {
+            builder.add_row(row, &[]).unwrap();
+        }
+        builder.build(None).unwrap()
+    }
+
+    fn prometheus(table: &DataTable<f64>, options: &PrometheusOptions) -> io::Result<String> {
+        let mut out = Vec::new();
+        table.write_prometheus(&mut out, options)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn writes_the_last_value_per_column() {
+        let table = table(&["temp.c", "load"], &[&[20.5, 1.0], &[21.0, f64::NAN]]);
+        let options = PrometheusOptions {
+            prefix: "printer_".into(),
+            labels: vec![("host".into(), "a\"b".into())],
+            ..Default::default()
+        };
+        assert_eq!(
+            prometheus(&table, &options).unwrap(),
+            "# TYPE printer_temp_c gauge\n\
+             printer_temp_c{host=\"a\\\"b\"} 21\n\
+             # TYPE printer_load gauge\n\
+             printer_load{host=\"a\\\"b\"} 1\n"
+        );
+    }
+
+    #[test]
+    fn rejects_columns_with_the_same_metric_name() {
+        let table = table(&["temp.a", "temp_a"], &[&[1.0, 2.0]]);
+        let err = prometheus(&table, &PrometheusOptions::default()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let options = PrometheusOptions {
+            names: HashMap::from([("temp.a".into(), "temp_dot_a".into())]),
+            ..Default::default()
+        };
+        assert!(prometheus(&table, &options).is_ok());
+    }
+}
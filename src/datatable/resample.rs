@@ -0,0 +1,278 @@
+use std::cmp::Ordering;
+
+use super::{datatable_error::DataTableError, DataTable};
+use crate::numeric::Numeric;
+
+/// Defines how values between two rows are computed by [`DataTable::resample`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Linear interpolation between the previous and the next value.
+    #[default]
+    Linear,
+    /// The value of the row with the nearest base data value, the previous one on ties.
+    Nearest,
+    /// The value of the last row at or before the base data value, like a step function.
+    Previous,
+}
+
+impl Interpolation {
+    /// Interpolates the value at `x` from `points` sorted by their first element.
+    fn apply(&self, points: &[(f64, f64)], x: f64) -> Option<f64> {
+        let pos = points.partition_point(|(px, _)| *px < x);
+        if let Some((_, y)) = points.get(pos).filter(|(px, _)| *px == x) {
+            return Some(*y);
+        }
+        let (before, after) = (pos.checked_sub(1).map(|p| points[p]), points.get(pos));
+        match (self, before, after) {
+            (_, Some((x0, y0)), Some((x1, y1))) => Some(match self {
+                Interpolation::Linear => y0 + (y1 - y0) * (x - x0) / (x1 - x0),
+                Interpolation::Nearest if x1 - x < x - x0 => *y1,
+                Interpolation::Nearest | Interpolation::Previous => y0,
+            }),
+            (Interpolation::Previous, Some((_, y0)), None) => Some(y0),
+            _ => None,
+        }
+    }
+}
+
+impl<T> DataTable<T>
+where
+    T: Numeric,
+{
+    /// Returns a table with one row per value of `new_base`, e.g. a uniform time grid, and the values
+    /// of each column interpolated from its rows without missing values.
+    ///
+    /// Values outside the range of the base data are missing, except for `Interpolation::Previous`
+    /// after the last row. Integer tables, which cannot hold missing values, use the nearest value instead.
+    /// Text columns take the text of the last row at or before each base data value.
+    pub fn resample(&self, new_base: &[T], interpolation: Interpolation) -> DataTable<T> {
+        let base = self.get_base_data();
+        let mut rows: Vec<usize> = (0..self.value_rows)
+            .filter(|i| !base[*i].is_missing())
+            .collect();
+        rows.sort_by(|a, b| base[*a].partial_cmp(&base[*b]).unwrap_or(Ordering::Equal));
+        let xs: Vec<_> = new_base
+            .iter()
+            .map(|x| x.to_f64().unwrap_or(f64::NAN))
+            .collect();
+
+        let value_data = self
+            .value_data
+            .iter()
+            .enumerate()
+            .map(|(index, col)| {
+                if Some(index) == self.base_data_index {
+                    return new_base.to_vec();
+                }
+                let points: Vec<_> = rows
+                    .iter()
+                    .filter(|row| !col[**row].is_missing())
+                    .filter_map(|row| Some((base[*row].to_f64()?, col[*row].to_f64()?)))
+                    .collect();
+                xs.iter()
+                    .map(|x| {
+                        interpolation
+                            .apply(&points, *x)
+                            .and_then(T::from)
+                            .or_else(T::missing)
+                            .or_else(|| {
+                                // Integer tables cannot hold missing values, use the nearest one instead.
+                                let (first, last) = (points.first()?, points.last()?);
+                                T::from(if *x < first.0 { first.1 } else { last.1 })
+                            })
+                            .unwrap_or_else(T::zero)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let text_data = self
+            .text_data
+            .iter()
+            .map(|col| {
+                new_base
+                    .iter()
+                    .map(|x| {
+                        let pos = rows.partition_point(|row| base[*row] <= *x);
+                        pos.checked_sub(1)
+                            .map(|p| col[rows[p]].clone())
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut table = DataTable::new(
+            self.value_columns,
+            Some(&self.value_names),
+            self.base_data_index,
+        )
+        .with_text_columns(&self.text_names);
+        table.value_data = value_data;
+        table.text_data = text_data;
+        table.base_data = new_base.to_vec();
+        table.value_rows = new_base.len();
+        table.metadata = self.metadata.clone();
+        table
+    }
+
+    /// Returns a table with at most `target_points` rows chosen by the largest-triangle-three-buckets
+    /// algorithm, which keeps the visual shape of the value column `column` over the base data,
+    /// e.g. to plot millions of rows.
+    ///
+    /// Rows with a missing base data or column value are dropped. The first and the last row are always kept.
+    pub fn downsample_lttb(
+        &self,
+        column: &str,
+        target_points: usize,
+    ) -> Result<DataTable<T>, DataTableError> {
+        let base = self.get_base_data();
+        let col = self.col_slice_by_name(column)?;
+        let (rows, points): (Vec<usize>, Vec<(f64, f64)>) = (0..self.value_rows)
+            .filter(|i| !base[*i].is_missing() && !col[*i].is_missing())
+            .filter_map(|i| Some((i, (base[i].to_f64()?, col[i].to_f64()?))))
+            .unzip();
+        if target_points >= rows.len() {
+            return Ok(self.take_rows(&rows));
+        }
+        if target_points < 3 {
+            let ends = [rows[0], rows[rows.len() - 1]];
+            return Ok(self.take_rows(&ends[..target_points]));
+        }
+
+        // The first and last point form their own buckets, the others are split evenly.
+        let bucket_size = (points.len() - 2) as f64 / (target_points - 2) as f64;
+        let bucket = |i: usize| {
+            let start = (i as f64 * bucket_size) as usize + 1;
+            let end = (((i + 1) as f64 * bucket_size) as usize + 1).min(points.len() - 1);
+            start..end
+        };
+        let mut selected = vec![0];
+        for i in 0..target_points - 2 {
+            let (ax, ay) = points[selected[selected.len() - 1]];
+            let next = if i + 1 < target_points - 2 {
+                bucket(i + 1)
+            } else {
+                points.len() - 1..points.len()
+            };
+            let count = next.len() as f64;
+            let (cx, cy) = points[next].iter().fold((0.0, 0.0), |(x, y), (px, py)| {
+                (x + px / count, y + py / count)
+            });
+            let area = |(bx, by): (f64, f64)| ((ax - cx) * (by - ay) - (ax - bx) * (cy - ay)).abs();
+            let best = bucket(i).max_by(|a, b| {
+                area(points[*a])
+                    .partial_cmp(&area(points[*b]))
+                    .unwrap_or(Ordering::Equal)
+            });
+            selected.extend(best);
+        }
+        selected.push(points.len() - 1);
+
+        let rows: Vec<_> = selected.iter().map(|i| rows[*i]).collect();
+        Ok(self.take_rows(&rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datatable::builder::DataTableBuilder;
+
+    fn table(time: &[f64], temp: &[f64]) -> DataTable<f64> {
+        DataTable::from_columns(
+            vec![
+                ("time".into(), time.to_vec()),
+                ("temp".into(), temp.to_vec()),
+            ],
+            Some("time"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resample_interpolates_between_rows() {
+        let data = table(&[0.0, 2.0, 4.0], &[10.0, 20.0, f64::NAN]);
+        let grid = [-1.0, 1.0, 2.0, 3.0];
+
+        let linear = data.resample(&grid, Interpolation::Linear);
+        let temp = linear.col_slice_by_name("temp").unwrap();
+        assert!(temp[0].is_nan());
+        assert_eq!(&temp[1..3], &[15.0, 20.0]);
+        assert!(temp[3].is_nan());
+        assert_eq!(linear.col_slice_by_name("time").unwrap(), &grid);
+
+        let previous = data.resample(&grid, Interpolation::Previous);
+        assert_eq!(
+            &previous.col_slice_by_name("temp").unwrap()[1..],
+            &[10.0, 20.0, 20.0]
+        );
+    }
+
+    #[test]
+    fn integer_tables_use_the_nearest_value_outside_the_data() {
+        let data = DataTable::<i64>::from_columns(
+            vec![("time".into(), vec![0, 10]), ("count".into(), vec![4, 8])],
+            Some("time"),
+        )
+        .unwrap();
+        let resampled = data.resample(&[-5, 5, 15], Interpolation::Linear);
+        assert_eq!(resampled.col_slice_by_name("count").unwrap(), &[4, 6, 8]);
+    }
+
+    #[test]
+    fn lttb_keeps_the_ends_and_the_peak() {
+        let time: Vec<f64> = (0..10).map(f64::from).collect();
+        let mut temp = vec![0.0; 10];
+        temp[4] = 100.0;
+        let data = table(&time, &temp);
+
+        let sampled = data.downsample_lttb("temp", 4).unwrap();
+        let times = sampled.col_slice_by_name("time").unwrap();
+        assert_eq!(times.len(), 4);
+        assert_eq!((times[0], times[3]), (0.0, 9.0));
+        assert!(times.contains(&4.0));
+
+        assert_eq!(data.downsample_lttb("temp", 20).unwrap().iter().len(), 10);
+        assert!(data.downsample_lttb("pressure", 4).is_err());
+    }
+
+    #[test]
+    fn resample_sorts_the_rows_and_takes_the_previous_text() {
+        let mut builder = DataTableBuilder::<f64>::new(&["time".into(), "temp".into()])
+            .unwrap()
+            .with_text_columns(&["level".into()])
+            .unwrap();
+        for (time, temp, level) in [(4.0, 40.0, "c"), (f64::NAN, 99.0, "x"), (0.0, 0.0, "a")] {
+            builder.add_row(&[time, temp], &[level.into()]).unwrap();
+        }
+        builder.add_row(&[2.0, 20.0], &["b".into()]).unwrap();
+        let data = builder.build(Some("time")).unwrap();
+
+        let nearest = data.resample(&[-1.0, 1.0, 3.0, 5.0], Interpolation::Nearest);
+        let temp = nearest.col_slice_by_name("temp").unwrap();
+        assert!(temp[0].is_nan() && temp[3].is_nan());
+        assert_eq!(&temp[1..3], &[0.0, 20.0]);
+        let level: Vec<_> = nearest.get_text_col_by_name("level").unwrap().collect();
+        assert_eq!(level, ["", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn resampling_an_empty_table_gives_missing_values() {
+        let resampled = table(&[], &[]).resample(&[1.0, 2.0], Interpolation::Previous);
+        assert_eq!(resampled.col_slice_by_name("time").unwrap(), &[1.0, 2.0]);
+        assert!(resampled
+            .col_slice_by_name("temp")
+            .unwrap()
+            .iter()
+            .all(|v| v.is_nan()));
+        assert!(
+            table(&[], &[])
+                .downsample_lttb("temp", 2)
+                .unwrap()
+                .iter()
+                .len()
+                == 0
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::{fmt::Display, io};
+
+use super::DataTable;
+use crate::numeric::Numeric;
+
+impl<T> DataTable<T>
+where
+    T: Numeric + Display,
+{
+    /// Writes SQL statements creating the table `table_name` with one column per value and text column
+    /// and inserting all rows in a single transaction, e.g. for `sqlite3 logs.db < table.sql`.
+    ///
+    /// Value columns are `REAL` columns, text columns `TEXT` columns. Missing and infinite values become `NULL`.
+    /// A table without base data column gets a leading `base` column with the base data.
+    pub fn to_sql(&self, writer: &mut impl io::Write, table_name: &str) -> io::Result<()> {
+        let base = self.get_base_data();
+        let with_base = self.base_data_index.is_none();
+        let value = |value: T| {
+            if value.is_missing() || !value.to_f64().is_some_and(f64::is_finite) {
+                "NULL".to_string()
+            } else {
+                value.to_string()
+            }
+        };
+
+        writeln!(writer, "BEGIN TRANSACTION;")?;
+        writeln!(writer, "{};", self.create_table(table_name))?;
+
+        for row in 0..self.value_rows {
+            let fields: Vec<_> = with_base
+                .then(|| value(base[row]))
+                .into_iter()
+                .chain(self.value_data.iter().map(|col| value(col[row])))
+                .chain(
+                    self.text_data
+                        .iter()
+                        .map(|col| format!("'{}'", col[row].replace('\'', "''"))),
+                )
+                .collect();
+            writeln!(
+                writer,
+                "INSERT INTO {} VALUES ({});",
+                identifier(table_name),
+                fields.join(", ")
+            )?;
+        }
+        writeln!(writer, "COMMIT;")
+    }
+
+    /// Creates the table `table_name` in the SQLite database `conn` and inserts all rows
+    /// with a prepared statement in a single transaction.
+    ///
+    /// The columns are the same as for [`DataTable::to_sql`], missing and infinite values become `NULL`.
+    #[cfg(feature = "sqlite")]
+    pub fn to_sqlite(
+        &self,
+        conn: &mut rusqlite::Connection,
+        table_name: &str,
+    ) -> rusqlite::Result<()> {
+        use rusqlite::types::Value;
+
+        let base = self.get_base_data();
+        let with_base = self.base_data_index.is_none();
+        let value = |value: T| match value.to_f64() {
+            Some(v) if !value.is_missing() && v.is_finite() => Value::Real(v),
+            _ => Value::Null,
+        };
+
+        let tx = conn.transaction()?;
+        tx.execute(&self.create_table(table_name), [])?;
+        {
+            let columns = usize::from(with_base) + self.value_data.len() + self.text_data.len();
+            let placeholders = vec!["?"; columns].join(", ");
+            let mut insert = tx.prepare(&format!(
+                "INSERT INTO {} VALUES ({})",
+                identifier(table_name),
+                placeholders
+            ))?;
+            for row in 0..self.value_rows {
+                let fields = with_base
+                    .then(|| value(base[row]))
+                    .into_iter()
+                    .chain(self.value_data.iter().map(|col| value(col[row])))
+                    .chain(
+                        self.text_data
+                            .iter()
+                            .map(|col| Value::Text(col[row].clone())),
+                    );
+                insert.execute(rusqlite::params_from_iter(fields))?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// The `CREATE TABLE` statement for the table `table_name`, without trailing semicolon.
+    fn create_table(&self, table_name: &str) -> String {
+        let columns: Vec<_> = self
+            .base_data_index
+            .is_none()
+            .then(|| format!("{} REAL", identifier("base")))
+            .into_iter()
+            .chain(
+                self.value_names
+                    .iter()
+                    .map(|n| format!("{} REAL", identifier(n))),
+            )
+            .chain(
+                self.text_names
+                    .iter()
+                    .map(|n| format!("{} TEXT", identifier(n))),
+            )
+            .collect();
+        format!(
+            "CREATE TABLE {} ({})",
+            identifier(table_name),
+            columns.join(", ")
+        )
+    }
+}
+
+/// Quotes a table or column name as SQL identifier.
+fn identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datatable::{builder::DataTableBuilder, DataTable};
+
+    fn table() -> DataTable<f64> {
+        let mut builder = DataTableBuilder::<f64>::new(&["time".into(), "temp".into()])
+            .unwrap()
+            .with_text_columns(&["level".into()])
+            .unwrap();
+        builder.add_row(&[1.0, 21.5], &["INFO".into()]).unwrap();
+        builder.add_row(&[2.0, f64::NAN], &["it's".into()]).unwrap();
+        builder.build(Some("time")).unwrap()
+    }
+
+    #[test]
+    fn to_sql_writes_a_transaction() {
+        let mut sql = Vec::new();
+        table().to_sql(&mut sql, "log").unwrap();
+        assert_eq!(
+            String::from_utf8(sql).unwrap(),
+            "BEGIN TRANSACTION;\n\
+             CREATE TABLE \"log\" (\"time\" REAL, \"temp\" REAL, \"level\" TEXT);\n\
+             INSERT INTO \"log\" VALUES (1, 21.5, 'INFO');\n\
+             INSERT INTO \"log\" VALUES (2, NULL, 'it''s');\n\
+             COMMIT;\n"
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn to_sqlite_inserts_all_rows() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        table().to_sqlite(&mut conn, "log").unwrap();
+        let rows: Vec<(f64, Option<f64>, String)> = conn
+            .prepare("SELECT time, temp, level FROM log ORDER BY time")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            [(1.0, Some(21.5), "INFO".into()), (2.0, None, "it's".into())]
+        );
+    }
+}
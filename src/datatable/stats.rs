@@ -0,0 +1,183 @@
+use std::cmp::Ordering;
+
+use super::{datatable_error::DataTableError, DataTable};
+use crate::numeric::Numeric;
+
+/// Summary statistics of a value column, see [`DataTable::describe`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub name: String,
+    /// The number of values which are not missing.
+    pub count: usize,
+    /// The number of missing values.
+    pub missing: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    /// The sample standard deviation, `None` for less than two values.
+    pub std: Option<f64>,
+}
+
+impl<T: Numeric> DataTable<T> {
+    /// Returns the summary statistics of every value column, e.g. for a quick sanity check of
+    /// the extracted data. Missing values are only counted.
+    pub fn describe(&self) -> Vec<ColumnStats> {
+        self.value_names
+            .iter()
+            .zip(&self.value_data)
+            .map(|(name, col)| {
+                let values: Vec<f64> = col
+                    .iter()
+                    .filter(|v| !v.is_missing())
+                    .filter_map(|v| v.to_f64())
+                    .collect();
+                let count = values.len();
+                let mean = (count > 0).then(|| values.iter().sum::<f64>() / count as f64);
+                let std = mean.filter(|_| count > 1).map(|mean| {
+                    let squares: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+                    (squares / (count - 1) as f64).sqrt()
+                });
+                ColumnStats {
+                    name: name.clone(),
+                    count,
+                    missing: col.len() - count,
+                    min: values.iter().copied().reduce(f64::min),
+                    max: values.iter().copied().reduce(f64::max),
+                    mean,
+                    std,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the `q`-quantile of the value column `column`, e.g. the median for `q = 0.5`,
+    /// interpolating linearly between the closest values. Missing values are ignored,
+    /// the quantile of a column without values is `None`.
+    pub fn quantile(&self, column: &str, q: f64) -> Result<Option<f64>, DataTableError> {
+        Ok(self.percentiles(column, &[q])?[0])
+    }
+
+    /// Returns the quantiles `qs` of the value column `column` like [`DataTable::quantile`],
+    /// e.g. `&[0.5, 0.95, 0.99]` for request latencies, sorting the values only once.
+    pub fn percentiles(
+        &self,
+        column: &str,
+        qs: &[f64],
+    ) -> Result<Vec<Option<f64>>, DataTableError> {
+        if let Some(q) = qs.iter().find(|q| !(0.0..=1.0).contains(*q)) {
+            return Err(DataTableError::InvalidQuantile(*q));
+        }
+        let mut values = self.float_values(column)?;
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        Ok(qs
+            .iter()
+            .map(|q| {
+                let last = values.len().checked_sub(1)?;
+                let position = q * last as f64;
+                let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+                let fraction = position - lower as f64;
+                Some(values[lower] + (values[upper] - values[lower]) * fraction)
+            })
+            .collect())
+    }
+
+    /// Counts the values of the value column `column` in `bins` bins of equal width between
+    /// the smallest and the largest value, e.g. to print the distribution of request durations.
+    ///
+    /// The result has the columns `lower`, `upper` and `count` with one row per bin,
+    /// `lower` being the base data. Every bin includes its lower edge, the last one also its upper edge.
+    /// Missing values are ignored, a column without values has no bins.
+    pub fn histogram(&self, column: &str, bins: usize) -> Result<DataTable<f64>, DataTableError> {
+        if bins == 0 {
+            return Err(DataTableError::NoBins);
+        }
+        let values = self.float_values(column)?;
+        let min = values.iter().copied().reduce(f64::min);
+        let max = values.iter().copied().reduce(f64::max);
+        let (min, max, bins) = match min.zip(max) {
+            Some((min, max)) if min == max => (min - 0.5, max + 0.5, bins),
+            Some((min, max)) => (min, max, bins),
+            None => (0.0, 0.0, 0),
+        };
+        let width = (max - min) / bins as f64;
+
+        let mut counts = vec![0.0; bins];
+        for value in values {
+            let bin = ((value - min) / width) as usize;
+            counts[bin.min(bins - 1)] += 1.0;
+        }
+        let edge = |i: usize| min + width * i as f64;
+        DataTable::from_columns(
+            vec![
+                ("lower".into(), (0..bins).map(edge).collect()),
+                ("upper".into(), (1..=bins).map(edge).collect()),
+                ("count".into(), counts),
+            ],
+            Some("lower"),
+        )
+    }
+
+    /// Returns the values of the value column `column` as `f64` without missing values.
+    fn float_values(&self, column: &str) -> Result<Vec<f64>, DataTableError> {
+        Ok(self
+            .col_slice_by_name(column)?
+            .iter()
+            .filter(|v| !v.is_missing())
+            .filter_map(|v| v.to_f64())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(latency: &[f64]) -> DataTable<f64> {
+        DataTable::from_columns(vec![("latency".into(), latency.to_vec())], None).unwrap()
+    }
+
+    #[test]
+    fn quantiles_interpolate_between_values() {
+        let data = table(&[40.0, f64::NAN, 10.0, 30.0, 20.0]);
+        assert_eq!(data.quantile("latency", 0.5).unwrap(), Some(25.0));
+        assert_eq!(
+            data.percentiles("latency", &[0.0, 1.0, 1.0 / 3.0]).unwrap(),
+            [Some(10.0), Some(40.0), Some(20.0)]
+        );
+        assert_eq!(table(&[f64::NAN]).quantile("latency", 0.5).unwrap(), None);
+        assert!(matches!(
+            data.quantile("latency", 1.5),
+            Err(DataTableError::InvalidQuantile(_))
+        ));
+    }
+
+    #[test]
+    fn the_last_bin_includes_the_largest_value() {
+        let histogram = table(&[1.0, 2.0, f64::NAN, 3.0, 4.0])
+            .histogram("latency", 3)
+            .unwrap();
+        assert_eq!(
+            histogram.col_slice_by_name("lower").unwrap(),
+            &[1.0, 2.0, 3.0]
+        );
+        assert_eq!(
+            histogram.col_slice_by_name("upper").unwrap(),
+            &[2.0, 3.0, 4.0]
+        );
+        assert_eq!(
+            histogram.col_slice_by_name("count").unwrap(),
+            &[1.0, 1.0, 2.0]
+        );
+
+        let equal = table(&[5.0, 5.0]).histogram("latency", 2).unwrap();
+        assert_eq!(equal.col_slice_by_name("lower").unwrap(), &[4.5, 5.0]);
+        assert_eq!(equal.col_slice_by_name("count").unwrap(), &[0.0, 2.0]);
+        let empty = table(&[]).histogram("latency", 2).unwrap();
+        assert_eq!(empty.col_slice_by_name("count").unwrap(), &[] as &[f64]);
+        assert!(matches!(
+            table(&[]).histogram("latency", 0),
+            Err(DataTableError::NoBins)
+        ));
+    }
+}
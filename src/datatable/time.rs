@@ -0,0 +1,87 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::{datatable_error::DataTableError, DataTable};
+use crate::numeric::Numeric;
+
+/// A point or span in time base data in seconds can be converted into,
+/// e.g. timestamps extracted with `ValueFormat::Timestamp`.
+pub trait BaseTime: Sized {
+    /// Converts seconds into the time, or returns `None` for missing or out of range seconds.
+    fn from_seconds(seconds: f64) -> Option<Self>;
+}
+
+/// Seconds since the unix epoch, see `TimeOrigin::Epoch`.
+impl BaseTime for DateTime<Utc> {
+    fn from_seconds(seconds: f64) -> Option<Self> {
+        DateTime::from_timestamp_micros(to_micros(seconds)?)
+    }
+}
+
+/// Seconds since an arbitrary start, like `TimeOrigin::FirstTimestamp` or midnight.
+impl BaseTime for Duration {
+    fn from_seconds(seconds: f64) -> Option<Self> {
+        Some(Duration::microseconds(to_micros(seconds)?))
+    }
+}
+
+fn to_micros(seconds: f64) -> Option<i64> {
+    let micros = (seconds * 1e6).round();
+    (micros.is_finite() && micros.abs() < i64::MAX as f64).then_some(micros as i64)
+}
+
+impl<T: Numeric> DataTable<T> {
+    /// Returns the base data as times, e.g. `base_times::<DateTime<Utc>>()` for timestamps.
+    /// Missing base data values are `None`.
+    pub fn base_times<B: BaseTime>(&self) -> impl Iterator<Item = Option<B>> + '_ {
+        self.get_base_data()
+            .iter()
+            .map(|b| b.to_f64().and_then(B::from_seconds))
+    }
+
+    /// Like [`DataTable::get_col_with_base`], but with the base data converted into times.
+    pub fn get_col_with_time<B: BaseTime>(
+        &self,
+        index: usize,
+    ) -> Result<impl Iterator<Item = (Option<B>, T)> + '_, DataTableError> {
+        Ok(self
+            .base_times()
+            .zip(self.col_slice(index)?.iter().copied()))
+    }
+
+    /// Like [`DataTable::get_col_by_name_with_base`], but with the base data converted into times.
+    pub fn get_col_by_name_with_time<B: BaseTime>(
+        &self,
+        name: &str,
+    ) -> Result<impl Iterator<Item = (Option<B>, T)> + '_, DataTableError> {
+        Ok(self
+            .base_times()
+            .zip(self.col_slice_by_name(name)?.iter().copied()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_data_is_converted_into_times() {
+        let data = DataTable::<f64>::from_columns(
+            vec![
+                ("time".into(), vec![1.5, f64::NAN, 1e300]),
+                ("temp".into(), vec![20.0, 21.0, 22.0]),
+            ],
+            Some("time"),
+        )
+        .unwrap();
+        let times: Vec<_> = data.base_times::<DateTime<Utc>>().collect();
+        assert_eq!(times[0], DateTime::from_timestamp_millis(1500));
+        assert_eq!(&times[1..], &[None, None]);
+
+        let durations: Vec<_> = data
+            .get_col_by_name_with_time::<Duration>("temp")
+            .unwrap()
+            .collect();
+        assert_eq!(durations[0], (Some(Duration::milliseconds(1500)), 20.0));
+        assert!(data.get_col_with_time::<Duration>(2).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+};
+
+use crate::chain::ChainReader;
+
+/// The compression formats which are detected in inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    /// Requires the `gzip` feature.
+    Gzip,
+    /// Requires the `zstd` feature.
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression from the first bytes of an input.
+    pub fn from_magic(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Detects the compression from a file extension like `.gz` or `.zst`.
+    pub fn from_extension(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") | Some("zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Wraps `reader` into a decoder for this compression.
+    ///
+    /// Fails with `io::ErrorKind::Unsupported` if the required feature is not enabled.
+    pub fn decoder<'a, R>(self, reader: R) -> io::Result<Box<dyn Read + Send + 'a>>
+    where
+        R: BufRead + Send + 'a,
+    {
+        match self {
+            Compression::None => Ok(Box::new(reader)),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Ok(Box::new(flate2::bufread::MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Ok(Box::new(zstd::Decoder::with_buffer(reader)?)),
+            #[allow(unreachable_patterns)]
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "{:?} compressed input requires the corresponding feature",
+                    self
+                ),
+            )),
+        }
+    }
+}
+
+/// Decompresses `reader` on the fly if its first bytes identify a supported compression format.
+/// Uncompressed inputs are passed through unchanged.
+pub fn decompress<'a, R>(reader: R) -> io::Result<Box<dyn Read + Send + 'a>>
+where
+    R: Read + Send + 'a,
+{
+    let mut reader = BufReader::new(reader);
+    let compression = Compression::from_magic(reader.fill_buf()?);
+    compression.decoder(reader)
+}
+
+/// Opens the file at `path`, decompressing it on the fly if it is compressed.
+///
+/// The compression is detected by magic bytes, or by the file extension if these are unknown.
+pub fn open(path: impl AsRef<Path>) -> io::Result<Box<dyn Read + Send>> {
+    let path = path.as_ref();
+    let mut reader = BufReader::new(File::open(path)?);
+    let compression = match Compression::from_magic(reader.fill_buf()?) {
+        Compression::None => Compression::from_extension(path),
+        compression => compression,
+    };
+    compression.decoder(reader)
+}
+
+/// Opens the files at `paths` like [`open`] and reads them back-to-back as one input, e.g. rotated logs
+/// in chronological order.
+pub fn open_all<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+) -> io::Result<ChainReader<Box<dyn Read + Send>>> {
+    let readers = paths
+        .into_iter()
+        .map(open)
+        .collect::<io::Result<Vec<_>>>()?;
+    Ok(ChainReader::new(readers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(input: &[u8]) -> io::Result<String> {
+        let mut text = String::new();
+        decompress(input)?.read_to_string(&mut text)?;
+        Ok(text)
+    }
+
+    #[test]
+    fn plain_input_is_passed_through() {
+        assert_eq!(read(b"temp=1\n").unwrap(), "temp=1\n");
+        assert_eq!(read(b"").unwrap(), "");
+        assert_eq!(
+            Compression::from_extension(Path::new("app.log.zst")),
+            Compression::Zstd
+        );
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_members_are_decompressed() {
+        use std::io::Write;
+
+        let mut gzip = vec![];
+        for member in ["temp=1\n", "temp=2\n"] {
+            let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::fast());
+            encoder.write_all(member.as_bytes()).unwrap();
+            gzip.extend(encoder.finish().unwrap());
+        }
+        assert_eq!(read(&gzip).unwrap(), "temp=1\ntemp=2\n");
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd_input_is_decompressed() {
+        let zstd = zstd::encode_all(&b"temp=1\n"[..], 0).unwrap();
+        assert_eq!(read(&zstd).unwrap(), "temp=1\n");
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn zstd_input_requires_the_feature() {
+        let error = read(&[0x28, 0xb5, 0x2f, 0xfd]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+    }
+}
@@ -0,0 +1,200 @@
+use std::{fmt, io};
+
+use crate::NamedRegex;
+
+/// A problem found during an extraction which did not stop it, but may explain missing values,
+/// see `Extractor::run_with_warnings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// The text matched for `column` in the line with the given 1-based number could not be parsed.
+    UnparseableValue {
+        column: String,
+        line: usize,
+        text: String,
+    },
+    /// The line with the given 1-based number could not be read and was skipped.
+    ReadError { line: usize, error: String },
+    /// The data regex with the given name did not match any line.
+    NeverMatched { regex: String },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::UnparseableValue { column, line, text } => write!(
+                f,
+                "could not parse '{}' for column '{}' in line {}",
+                text, column, line
+            ),
+            Warning::ReadError { line, error } => {
+                write!(f, "could not read line {}: {}", line, error)
+            }
+            Warning::NeverMatched { regex } => write!(f, "'{}' did not match any line", regex),
+        }
+    }
+}
+
+/// The role of an expression within an extraction, see [`ExpressionMatches`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpressionKind {
+    Data,
+    Text,
+    Context,
+    Include,
+    Exclude,
+    RecordStart,
+    StartMarker,
+    StopMarker,
+}
+
+impl fmt::Display for ExpressionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExpressionKind::Data => "data",
+            ExpressionKind::Text => "text",
+            ExpressionKind::Context => "context",
+            ExpressionKind::Include => "include",
+            ExpressionKind::Exclude => "exclude",
+            ExpressionKind::RecordStart => "record start",
+            ExpressionKind::StartMarker => "start marker",
+            ExpressionKind::StopMarker => "stop marker",
+        })
+    }
+}
+
+/// The number of lines an expression matched, see `Extractor::count_matches`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpressionMatches {
+    pub kind: ExpressionKind,
+    /// The name of a data, text or context regex, the pattern for the other expressions.
+    pub name: String,
+    pub pattern: String,
+    pub lines: usize,
+}
+
+/// How often the expressions of an extractor matched the first lines of an input,
+/// see `Extractor::count_matches`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchCounts {
+    /// The number of lines scanned.
+    pub lines: usize,
+    /// The number of lines passing the include and exclude expressions.
+    pub passed: usize,
+    pub expressions: Vec<ExpressionMatches>,
+}
+
+/// The issues found while extracting the values of one line.
+#[derive(Debug, Default)]
+pub(crate) struct LineIssues {
+    /// The indices of the data regexes which matched.
+    pub matched: Vec<usize>,
+    /// The column names and texts of the matches which could not be parsed.
+    pub unparsed: Vec<(String, String)>,
+}
+
+/// Collects the warnings of an extraction and counts the lines each data regex matched.
+///
+/// The warnings about single lines are also logged at debug level.
+#[derive(Debug)]
+pub(crate) struct Diagnostics {
+    regex_names: Vec<String>,
+    matched: Vec<usize>,
+    /// The number of lines passing the filters, and of those without any match.
+    lines: usize,
+    lines_without_match: usize,
+    warnings: Option<Vec<Warning>>,
+}
+
+impl Diagnostics {
+    pub fn new(data_regex: &[NamedRegex]) -> Self {
+        Self {
+            regex_names: data_regex.iter().map(|r| r.name.clone()).collect(),
+            matched: vec![0; data_regex.len()],
+            lines: 0,
+            lines_without_match: 0,
+            warnings: None,
+        }
+    }
+
+    /// Starts collecting warnings, before only the matched regexes are tracked.
+    pub fn collect_warnings(&mut self) {
+        self.warnings.get_or_insert_with(Vec::new);
+    }
+
+    /// Adds the issues of the line with the given number.
+    pub fn add_line(&mut self, line: usize, issues: LineIssues) {
+        self.lines += 1;
+        if issues.matched.is_empty() {
+            self.lines_without_match += 1;
+        }
+        for index in issues.matched {
+            self.matched[index] += 1;
+        }
+        for (column, text) in issues.unparsed {
+            self.add_warning(Warning::UnparseableValue { column, line, text });
+        }
+    }
+
+    /// Adds the error of the line with the given number which could not be read.
+    pub fn add_read_error(&mut self, line: usize, error: &io::Error) {
+        self.add_warning(Warning::ReadError {
+            line,
+            error: error.to_string(),
+        });
+    }
+
+    fn add_warning(&mut self, warning: Warning) {
+        log::debug!("{}", warning);
+        if let Some(warnings) = &mut self.warnings {
+            warnings.push(warning);
+        }
+    }
+
+    /// Returns the names of the data regexes which did not match any line so far.
+    pub fn unmatched(&self) -> impl Iterator<Item = &str> {
+        self.regex_names
+            .iter()
+            .zip(&self.matched)
+            .filter(|(_, matched)| **matched == 0)
+            .map(|(regex, _)| regex.as_str())
+    }
+
+    /// Adds the regexes which never matched, once the input is exhausted.
+    pub fn finish(&mut self) {
+        let unmatched: Vec<_> = self
+            .unmatched()
+            .map(|regex| Warning::NeverMatched {
+                regex: regex.to_string(),
+            })
+            .collect();
+        for warning in unmatched {
+            log::warn!("{}", warning);
+            if let Some(warnings) = &mut self.warnings {
+                warnings.push(warning);
+            }
+        }
+    }
+
+    /// Logs how many lines were read and passed the filters, how many rows were extracted from them
+    /// and how many lines each data regex matched.
+    pub fn log_summary(&self, lines_read: usize, rows: usize) {
+        log::info!(
+            "{} lines read, {} passed the filters, {} of them without any match, {} rows extracted",
+            lines_read,
+            self.lines,
+            self.lines_without_match,
+            rows
+        );
+        for (regex, matched) in self.regex_names.iter().zip(&self.matched) {
+            log::info!("'{}' matched {} lines", regex, matched);
+        }
+    }
+
+    /// Returns the warnings collected so far.
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        self.warnings
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
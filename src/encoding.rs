@@ -0,0 +1,43 @@
+use std::io::Read;
+
+use encoding_rs_io::DecodeReaderBytesBuilder;
+
+pub use encoding_rs::Encoding;
+
+/// Transcodes `reader` from `encoding`, e.g. `encoding_rs::WINDOWS_1252` for Latin-1 or `encoding_rs::UTF_16LE`,
+/// into UTF-8 on the fly.
+///
+/// The result can be passed to all extraction and filter functions reading from a `Read`.
+/// A byte order mark takes precedence over `encoding`, invalid sequences are replaced by `U+FFFD`.
+pub fn decode<R: Read>(reader: R, encoding: &'static Encoding) -> impl Read {
+    DecodeReaderBytesBuilder::new()
+        .encoding(Some(encoding))
+        .bom_override(true)
+        .build(reader)
+}
+
+/// Looks up an encoding by a label like `latin1`, `utf-16le` or `shift_jis`.
+pub fn for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(input: &[u8], label: &str) -> String {
+        let mut text = String::new();
+        decode(input, for_label(label).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn inputs_are_transcoded_to_utf8() {
+        assert_eq!(read(b"temp=20\xb0C\n", "latin1"), "temp=20°C\n");
+        assert_eq!(read(b"t\0=\x001\0", "utf-16le"), "t=1");
+        assert_eq!(read(b"\xef\xbb\xbft=1", "latin1"), "t=1");
+        assert!(for_label("klingon").is_none());
+    }
+}
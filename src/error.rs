@@ -1,22 +1,95 @@
-use std::error::Error;
+use std::io;
+
+use thiserror::Error;
 
 use crate::datatable::datatable_error::DataTableError;
 
-#[derive(Debug)]
+/// A `NamedRegex` could not be created because its expression is invalid.
+#[derive(Debug, Error)]
+#[error("invalid regular expression '{pattern}' for '{name}'")]
+pub struct RegexBuildError {
+    pub name: String,
+    pub pattern: String,
+    /// The compilation error, describing what is wrong with the expression.
+    #[source]
+    pub source: fancy_regex::Error,
+}
+
+/// An `ExtractionConfig` could not be loaded.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("could not read the config file")]
+    Io(#[from] io::Error),
+    #[cfg(feature = "json")]
+    #[error("invalid config")]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "toml")]
+    #[error("invalid config")]
+    Toml(#[from] toml::de::Error),
+    #[cfg(feature = "yaml")]
+    #[error("invalid config")]
+    Yaml(#[from] serde_yaml::Error),
+    /// The config file has an extension of an unsupported format.
+    #[error("unsupported config format '{0}'")]
+    UnsupportedFormat(String),
+}
+
+/// The maximum number of characters of a line quoted in an error.
+const SNIPPET_LENGTH: usize = 60;
+
+#[derive(Debug, Error)]
 pub enum ExtractionError {
-    DataTable(DataTableError),
-    ReadError(std::io::Error),
+    #[error("could not build the data table")]
+    DataTable(#[from] DataTableError),
+    /// The line with the given 1-based number could not be read, e.g. because of invalid UTF-8.
+    #[error("could not read line {line}")]
+    ReadError {
+        line: usize,
+        #[source]
+        source: io::Error,
+    },
+    /// A value of the named column was missing and its `MissingPolicy` is `Error`.
+    /// `line` is the 1-based number of the line completing the row, `snippet` its beginning.
+    #[error("missing value for column '{column}' in line {line}: '{snippet}'")]
+    MissingValue {
+        column: String,
+        line: usize,
+        snippet: String,
+    },
+    /// The data regex with the given name did not match any line, see `Extractor::require_matches`.
+    #[error("the data regex '{regex}' did not match any line")]
+    NoMatches { regex: String },
+    /// The extraction was cancelled, see `Extractor::cancel_token`.
+    #[error("the extraction was cancelled")]
+    Cancelled,
 }
 
-impl std::fmt::Display for ExtractionError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+/// Returns the beginning of `line` for quoting it in an error, with the line breaks of records escaped.
+pub(crate) fn snippet(line: &str) -> String {
+    let mut snippet: String = line.chars().take(SNIPPET_LENGTH).collect();
+    if snippet.len() < line.len() {
+        snippet.push_str("...");
     }
+    snippet.replace('\n', "\\n")
 }
-impl Error for ExtractionError {}
 
-impl From<DataTableError> for ExtractionError {
-    fn from(value: DataTableError) -> Self {
-        Self::DataTable(value)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippets_are_shortened_and_escaped() {
+        assert_eq!(snippet("t=1\nh=2"), "t=1\\nh=2");
+        let long = "x".repeat(SNIPPET_LENGTH + 1);
+        assert_eq!(snippet(&long), format!("{}...", &long[..SNIPPET_LENGTH]));
+        let error = ExtractionError::MissingValue {
+            column: "h".into(),
+            line: 2,
+            snippet: snippet("t=2"),
+        };
+        assert_eq!(
+            error.to_string(),
+            "missing value for column 'h' in line 2: 't=2'"
+        );
     }
 }
@@ -0,0 +1,603 @@
+use std::{collections::VecDeque, io, io::Read, sync::Arc};
+
+use crate::{
+    diagnostics::{Diagnostics, LineIssues, Warning},
+    error::{snippet, ExtractionError},
+    extractor::{Extractor, MissingPolicy},
+    filter_iter::FilterIter,
+    format::{TimeOrigin, ValueFormat},
+    get_rows, get_texts, get_values,
+    numeric::Numeric,
+    prefilter::Prefilter,
+    NamedRegex, Row, RowData,
+};
+
+#[cfg(feature = "rayon")]
+const PARALLEL_CHUNK_SIZE: usize = 4096;
+
+/// Iterator over the rows extracted from a reader, see [`Extractor::iter`].
+pub struct ExtractIter<Reader, T> {
+    lines: FilterIter<Reader>,
+    config: Extractor<T>,
+    names: Arc<[String]>,
+    text_names: Arc<[String]>,
+    processor: LineProcessor<T>,
+    pending: VecDeque<Result<RowData<T>, ExtractionError>>,
+    emitted: usize,
+    done: bool,
+}
+
+impl<Reader, T> ExtractIter<Reader, T>
+where
+    Reader: Read,
+    T: Numeric,
+{
+    pub(crate) fn new(reader: Reader, config: Extractor<T>) -> Self {
+        Self {
+            lines: config.filter_lines(reader),
+            names: config.column_names().into(),
+            text_names: config.text_column_names().into(),
+            processor: LineProcessor::new(&config),
+            config,
+            pending: VecDeque::new(),
+            emitted: 0,
+            done: false,
+        }
+    }
+
+    /// Returns the number of lines read so far which contained invalid UTF-8,
+    /// see [`Extractor::lossy_utf8`].
+    pub fn lossy_lines(&self) -> usize {
+        self.lines.lossy_lines()
+    }
+
+    /// Collects warnings about unparseable values, unreadable lines and data regexes which never
+    /// matched, see [`ExtractIter::take_warnings`].
+    pub fn with_warnings(mut self) -> Self {
+        self.processor.diagnostics.collect_warnings();
+        self
+    }
+
+    /// Returns the warnings collected since the last call. The data regexes which never matched
+    /// are only reported once the input is exhausted.
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        self.processor.diagnostics.take_warnings()
+    }
+
+    /// Returns the names of the data regexes which have not matched any line read so far.
+    pub(crate) fn unmatched(&self) -> impl Iterator<Item = &str> {
+        self.processor.diagnostics.unmatched()
+    }
+
+    /// Reads the next line and queues its rows. Returns `false` at the end of the input.
+    fn read_line(&mut self) -> bool {
+        let (config, processor) = (&self.config, &mut self.processor);
+        match self
+            .lines
+            .next_with(|line| processor.update_context(line, config))
+        {
+            Some(line) => {
+                processor.process(config, self.lines.line_number(), line, &mut self.pending);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reads a chunk of lines and queues their rows, matching the lines in parallel.
+    #[cfg(feature = "rayon")]
+    fn read_chunk(&mut self) -> bool {
+        use rayon::prelude::*;
+
+        let (config, processor) = (&self.config, &mut self.processor);
+        let mut lines = Vec::with_capacity(PARALLEL_CHUNK_SIZE);
+        while lines.len() < PARALLEL_CHUNK_SIZE && !config.is_cancelled() {
+            match self
+                .lines
+                .next_with(|line| processor.update_context(line, config))
+            {
+                Some(line) => {
+                    lines.push((self.lines.line_number(), line, processor.context.clone()))
+                }
+                None => break,
+            }
+        }
+        if lines.is_empty() {
+            // Unless cancelled, the input has ended.
+            return config.is_cancelled();
+        }
+
+        let prefilter = &processor.prefilter;
+        let rows: Vec<_> = lines
+            .into_par_iter()
+            .map(|(number, line, context)| {
+                let rows = line.map(|line| {
+                    let (rows, issues) = extract_rows(&line, config, prefilter, &context);
+                    (line, rows, issues)
+                });
+                (number, rows)
+            })
+            .collect();
+        for (number, rows) in rows {
+            processor.queue(number, rows, &mut self.pending);
+        }
+        true
+    }
+}
+
+impl<Reader, T> Iterator for ExtractIter<Reader, T>
+where
+    Reader: Read,
+    T: Numeric,
+{
+    type Item = Result<Row<T>, ExtractionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.config.is_full(self.emitted) {
+                return None;
+            }
+            if let Some(row) = self.pending.pop_front() {
+                if row.is_ok() {
+                    self.emitted += 1;
+                }
+                return Some(row.map(|row| Row {
+                    names: self.names.clone(),
+                    values: row.values,
+                    text_names: self.text_names.clone(),
+                    texts: row.texts,
+                }));
+            }
+            if self.done {
+                return None;
+            }
+            if self.config.is_cancelled() {
+                self.done = true;
+                return Some(Err(ExtractionError::Cancelled));
+            }
+
+            #[cfg(feature = "rayon")]
+            let more = if self.config.parallel {
+                self.read_chunk()
+            } else {
+                self.read_line()
+            };
+            #[cfg(not(feature = "rayon"))]
+            let more = self.read_line();
+
+            // Input ending because it was cancelled, like a followed file, is reported as cancellation.
+            if !more && !self.config.is_cancelled() {
+                self.done = true;
+                self.processor.finish(
+                    &self.config,
+                    (self.lines.lines_read(), self.lines.lossy_lines()),
+                    self.emitted,
+                    &mut self.pending,
+                );
+            }
+        }
+    }
+}
+
+/// The extraction of the rows from each line passing the filters, shared by [`ExtractIter`] and
+/// `Extractor::run_async`, which only differ in how they read the lines.
+pub(crate) struct LineProcessor<T> {
+    context: Context<T>,
+    prefilter: Prefilter,
+    pipeline: RowPipeline<T>,
+    diagnostics: Diagnostics,
+}
+
+impl<T> LineProcessor<T>
+where
+    T: Numeric,
+{
+    pub fn new(config: &Extractor<T>) -> Self {
+        Self {
+            context: Context::new(config),
+            prefilter: Prefilter::new(config.data_regex.iter().map(|r| &r.regex)),
+            pipeline: RowPipeline::new(config),
+            diagnostics: Diagnostics::new(&config.data_regex),
+        }
+    }
+
+    /// Updates the context columns from `line`, which has to be called for every line read,
+    /// including the ones filtered out.
+    pub fn update_context(&mut self, line: &str, config: &Extractor<T>) {
+        self.context.update(line, config);
+    }
+
+    /// Extracts the rows of the line with the given number and queues the completed ones in `out`,
+    /// or a `ExtractionError::ReadError` if it could not be read.
+    pub fn process(
+        &mut self,
+        config: &Extractor<T>,
+        line_number: usize,
+        line: io::Result<String>,
+        out: &mut VecDeque<Result<RowData<T>, ExtractionError>>,
+    ) {
+        let rows = line.map(|line| {
+            let (rows, issues) = extract_rows(&line, config, &self.prefilter, &self.context);
+            (line, rows, issues)
+        });
+        self.queue(line_number, rows, out);
+    }
+
+    /// Passes the rows extracted from a line through the pipeline, in input order.
+    fn queue(
+        &mut self,
+        line_number: usize,
+        rows: io::Result<ExtractedLine<T>>,
+        out: &mut VecDeque<Result<RowData<T>, ExtractionError>>,
+    ) {
+        match rows {
+            Ok((line, rows, issues)) => {
+                self.diagnostics.add_line(line_number, issues);
+                self.pipeline.process(rows, line_number, &line, out);
+            }
+            Err(source) => {
+                self.diagnostics.add_read_error(line_number, &source);
+                out.push_back(Err(ExtractionError::ReadError {
+                    line: line_number,
+                    source,
+                }));
+            }
+        }
+    }
+
+    /// Logs the summary once the input has ended, given the number of lines read and of lossy lines,
+    /// and the number of rows returned before `out`. Queues a `ExtractionError::NoMatches` for every
+    /// data regex which never matched if they are required.
+    pub fn finish(
+        &mut self,
+        config: &Extractor<T>,
+        (lines_read, lossy_lines): (usize, usize),
+        returned: usize,
+        out: &mut VecDeque<Result<RowData<T>, ExtractionError>>,
+    ) {
+        self.diagnostics.finish();
+        let rows = returned + out.iter().filter(|row| row.is_ok()).count();
+        self.diagnostics.log_summary(lines_read, rows);
+        if lossy_lines > 0 {
+            log::info!("{} lines contained invalid UTF-8", lossy_lines);
+        }
+        if config.require_matches {
+            out.extend(self.diagnostics.unmatched().map(|regex| {
+                Err(ExtractionError::NoMatches {
+                    regex: regex.to_string(),
+                })
+            }));
+        }
+    }
+}
+
+/// A line with the rows extracted from it and the issues found doing so.
+type ExtractedLine<T> = (String, Vec<RowData<Option<T>>>, LineIssues);
+
+/// Extracts the rows of `line` with the current values of the context columns.
+fn extract_rows<T: Numeric>(
+    line: &str,
+    config: &Extractor<T>,
+    prefilter: &Prefilter,
+    context: &Context<T>,
+) -> (Vec<RowData<Option<T>>>, LineIssues) {
+    let mut issues = LineIssues::default();
+    let mut rows = get_rows(
+        line,
+        &config.data_regex,
+        config.group,
+        prefilter,
+        &mut issues,
+    );
+    context.append_to(&mut rows);
+    (rows, issues)
+}
+
+/// The sequential processing of the rows extracted from each line, in input order.
+pub(crate) struct RowPipeline<T> {
+    origins: TimeOrigins<T>,
+    assembler: RowAssembler<T>,
+    missing: MissingValues<T>,
+}
+
+impl<T> RowPipeline<T>
+where
+    T: Numeric,
+{
+    pub fn new(config: &Extractor<T>) -> Self {
+        Self {
+            origins: TimeOrigins::new(config),
+            assembler: RowAssembler::new(config),
+            missing: MissingValues::new(config),
+        }
+    }
+
+    /// Processes the rows of `line`, the line with the given number, and queues the completed ones in `out`.
+    pub fn process(
+        &mut self,
+        mut rows: Vec<RowData<Option<T>>>,
+        line_number: usize,
+        line: &str,
+        out: &mut VecDeque<Result<RowData<T>, ExtractionError>>,
+    ) {
+        self.origins.apply(&mut rows);
+        for row in rows {
+            let Some(row) = self.assembler.push(row) else {
+                continue;
+            };
+            match self.missing.apply(row, line_number, line) {
+                Ok(Some(row)) => out.push_back(Ok(row)),
+                Ok(None) => {}
+                Err(e) => out.push_back(Err(e)),
+            }
+        }
+    }
+}
+
+/// Merges the rows of several lines until all required columns have a value,
+/// see `NamedRegex::with_required`.
+pub(crate) struct RowAssembler<T> {
+    required_values: Vec<usize>,
+    required_texts: Vec<usize>,
+    current: Option<RowData<Option<T>>>,
+}
+
+impl<T> RowAssembler<T>
+where
+    T: Numeric,
+{
+    pub fn new(config: &Extractor<T>) -> Self {
+        let required = |text: bool| -> Vec<usize> {
+            config
+                .data_regex
+                .iter()
+                .chain(&config.context_regex)
+                .filter(|rgx| rgx.is_text() == text)
+                .flat_map(|rgx| std::iter::repeat_n(rgx.required, rgx.column_names().len()))
+                .enumerate()
+                .filter(|(_, required)| *required)
+                .map(|(index, _)| index)
+                .collect()
+        };
+        Self {
+            required_values: required(false),
+            required_texts: required(true),
+            current: None,
+        }
+    }
+
+    /// Adds a row, returning the merged row once all required columns have a value.
+    /// Without required columns every row is returned unchanged.
+    pub fn push(&mut self, row: RowData<Option<T>>) -> Option<RowData<Option<T>>> {
+        if self.required_values.is_empty() && self.required_texts.is_empty() {
+            return Some(row);
+        }
+
+        let current = match self.current.take() {
+            Some(mut current) => {
+                for (value, new) in current.values.iter_mut().zip(row.values) {
+                    if new.is_some() {
+                        *value = new;
+                    }
+                }
+                for (text, new) in current.texts.iter_mut().zip(row.texts) {
+                    if !new.is_empty() {
+                        *text = new;
+                    }
+                }
+                current
+            }
+            None => row,
+        };
+
+        let complete = self
+            .required_values
+            .iter()
+            .all(|i| current.values[*i].is_some())
+            && self
+                .required_texts
+                .iter()
+                .all(|i| !current.texts[*i].is_empty());
+        if complete {
+            Some(current)
+        } else {
+            self.current = Some(current);
+            None
+        }
+    }
+}
+
+/// The values of the context regexes, which stick to all rows following their last match.
+#[derive(Clone)]
+pub(crate) struct Context<T> {
+    values: Vec<Option<T>>,
+    texts: Vec<String>,
+}
+
+impl<T> Context<T>
+where
+    T: Numeric,
+{
+    pub fn new(config: &Extractor<T>) -> Self {
+        let (texts, values): (Vec<_>, Vec<_>) =
+            config.context_regex.iter().partition(|r| r.is_text());
+        let count = |regexes: Vec<&NamedRegex>| -> usize {
+            regexes.iter().map(|r| r.column_names().len()).sum()
+        };
+        Self {
+            values: vec![None; count(values)],
+            texts: vec![String::new(); count(texts)],
+        }
+    }
+
+    /// Updates the values of all context regexes matching `line`.
+    pub fn update(&mut self, line: &str, config: &Extractor<T>) {
+        let (texts, values): (Vec<_>, Vec<_>) =
+            config.context_regex.iter().partition(|r| r.is_text());
+
+        let matched = values
+            .into_iter()
+            .flat_map(|rgx| get_values(line, rgx, config.group))
+            .map(|values| values.into_iter().next().flatten());
+        for (value, matched) in self.values.iter_mut().zip(matched) {
+            if matched.is_some() {
+                *value = matched;
+            }
+        }
+
+        let matched = texts
+            .into_iter()
+            .flat_map(|rgx| get_texts(line, rgx, config.group))
+            .map(|texts| texts.into_iter().next().flatten());
+        for (text, matched) in self.texts.iter_mut().zip(matched) {
+            if let Some(t) = matched {
+                *text = t;
+            }
+        }
+    }
+
+    pub fn append_to(&self, rows: &mut [RowData<Option<T>>]) {
+        for row in rows {
+            row.values.extend_from_slice(&self.values);
+            row.texts.extend_from_slice(&self.texts);
+        }
+    }
+}
+
+/// Makes timestamp columns relative to their first value, see `TimeOrigin::FirstTimestamp`.
+pub(crate) struct TimeOrigins<T> {
+    columns: Vec<(usize, Option<T>)>,
+}
+
+impl<T> TimeOrigins<T>
+where
+    T: Numeric,
+{
+    pub fn new(config: &Extractor<T>) -> Self {
+        let columns = config
+            .data_regex
+            .iter()
+            .chain(&config.context_regex)
+            .filter(|rgx| !rgx.is_text())
+            .flat_map(|rgx| {
+                let relative = matches!(
+                    rgx.format,
+                    ValueFormat::Timestamp {
+                        origin: TimeOrigin::FirstTimestamp,
+                        ..
+                    }
+                );
+                std::iter::repeat_n(relative, rgx.column_names().len())
+            })
+            .enumerate()
+            .filter(|(_, relative)| *relative)
+            .map(|(index, _)| (index, None))
+            .collect();
+        Self { columns }
+    }
+
+    pub fn apply(&mut self, rows: &mut [RowData<Option<T>>]) {
+        for row in rows {
+            for (index, origin) in self.columns.iter_mut() {
+                if let Some(value) = &mut row.values[*index] {
+                    if !value.is_missing() {
+                        *value = *value - *origin.get_or_insert(*value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Replaces missing values according to the `MissingPolicy` of their column.
+pub(crate) struct MissingValues<T> {
+    names: Vec<String>,
+    policies: Vec<MissingPolicy>,
+    last: Vec<Option<T>>,
+}
+
+impl<T> MissingValues<T>
+where
+    T: Numeric,
+{
+    pub fn new(config: &Extractor<T>) -> Self {
+        let names = config.column_names();
+        let policies = names
+            .iter()
+            .map(|n| {
+                config
+                    .column_missing_policy
+                    .get(n)
+                    .copied()
+                    .unwrap_or(config.missing_policy)
+            })
+            .collect();
+        Self {
+            last: vec![None; names.len()],
+            names,
+            policies,
+        }
+    }
+
+    /// Returns the completed row, or `None` if the row has to be skipped.
+    /// `line_number` and `line` are only used to report errors.
+    pub fn apply(
+        &mut self,
+        row: RowData<Option<T>>,
+        line_number: usize,
+        line: &str,
+    ) -> Result<Option<RowData<T>>, ExtractionError> {
+        for (last, value) in self.last.iter_mut().zip(&row.values) {
+            if value.is_some() {
+                *last = *value;
+            }
+        }
+
+        let mut values = Vec::with_capacity(row.values.len());
+        for (i, value) in row.values.into_iter().enumerate() {
+            let value = match (value, self.policies[i]) {
+                (Some(v), _) => Some(v),
+                (None, MissingPolicy::KeepNaN) => T::missing(),
+                (None, MissingPolicy::SkipRow) => None,
+                (None, MissingPolicy::ForwardFill) => self.last[i].or_else(T::missing),
+                (None, MissingPolicy::Zero) => Some(T::zero()),
+                (None, MissingPolicy::Error) => {
+                    return Err(ExtractionError::MissingValue {
+                        column: self.names[i].clone(),
+                        line: line_number,
+                        snippet: snippet(line),
+                    })
+                }
+            };
+            match value {
+                Some(v) => values.push(v),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(RowData {
+            values,
+            texts: row.texts,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_line_yields_a_row_or_a_read_error() {
+        let extractor = Extractor::<f64>::new()
+            .with_data_regex(NamedRegex::new_from_string("temp", r"temp=(\d+)").unwrap())
+            .group_mode(true);
+        let mut rows = extractor.iter(&b"temp=1\nidle\n\xff\ntemp=3\n"[..]);
+        assert_eq!(rows.next().unwrap().unwrap().get("temp"), Some(1.0));
+        assert!(rows.next().unwrap().unwrap().values()[0].is_nan());
+        assert!(matches!(
+            rows.next(),
+            Some(Err(ExtractionError::ReadError { line: 3, .. }))
+        ));
+        assert_eq!(rows.next().unwrap().unwrap().values(), &[3.0]);
+        assert!(rows.next().is_none());
+    }
+}
@@ -0,0 +1,930 @@
+use std::{
+    collections::HashMap,
+    io::Read,
+    marker::PhantomData,
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use fancy_regex::Regex;
+
+pub use crate::extract_iter::ExtractIter;
+#[cfg(feature = "async")]
+use crate::{async_filter_iter::AsyncFilterIter, extract_iter::LineProcessor};
+
+use crate::{
+    datatable::{builder::DataTableBuilder, DataTable},
+    diagnostics::{ExpressionKind, ExpressionMatches, MatchCounts, Warning},
+    error::ExtractionError,
+    filter_iter::FilterIter,
+    follow::FollowReader,
+    format::NumberFormat,
+    numeric::Numeric,
+    NamedRegex, Row,
+};
+
+/// Defines how a numeric value which could not be extracted from a line is handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MissingPolicy {
+    /// The value is NaN. Rows are skipped for types without NaN like integers.
+    #[default]
+    KeepNaN,
+    /// The whole row is skipped.
+    SkipRow,
+    /// The last extracted value of the column is used, or NaN before the first one.
+    ForwardFill,
+    /// The value is zero.
+    Zero,
+    /// The extraction fails with `ExtractionError::MissingValue`.
+    Error,
+}
+
+/// Configures and runs the extraction of data from line based inputs.
+///
+/// ```no_run
+/// # use regextractor::{extractor::Extractor, datatable::DataTable, NamedRegex};
+/// # use fancy_regex::Regex;
+/// let file = std::fs::File::open("print.log").unwrap();
+/// let table: DataTable<f64> = Extractor::new()
+///     .with_data_regex(NamedRegex::new_from_string("temp", r"T:([\d.]+)").unwrap())
+///     .include(Regex::new("^ok").unwrap())
+///     .group_mode(true)
+///     .run(file)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Extractor<T> {
+    pub(crate) data_regex: Vec<NamedRegex>,
+    pub(crate) context_regex: Vec<NamedRegex>,
+    pub(crate) included_lines: Vec<Regex>,
+    pub(crate) excluded_lines: Vec<Regex>,
+    pub(crate) base_data_name: Option<String>,
+    pub(crate) group: bool,
+    pub(crate) record_start: Option<Regex>,
+    pub(crate) start_marker: Option<Regex>,
+    pub(crate) stop_marker: Option<Regex>,
+    pub(crate) lossy_utf8: bool,
+    pub(crate) skip_lines: usize,
+    pub(crate) stride: usize,
+    pub(crate) cancel: Option<Arc<AtomicBool>>,
+    pub(crate) max_rows: Option<usize>,
+    pub(crate) capacity: usize,
+    pub(crate) require_matches: bool,
+    pub(crate) fail_on_read_errors: bool,
+    pub(crate) number_format: Option<NumberFormat>,
+    pub(crate) missing_policy: MissingPolicy,
+    pub(crate) column_missing_policy: HashMap<String, MissingPolicy>,
+    #[cfg(feature = "rayon")]
+    pub(crate) parallel: bool,
+    pub(crate) value_type: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for Extractor<T> {
+    fn default() -> Self {
+        Self {
+            data_regex: vec![],
+            context_regex: vec![],
+            included_lines: vec![],
+            excluded_lines: vec![],
+            base_data_name: None,
+            group: false,
+            record_start: None,
+            start_marker: None,
+            stop_marker: None,
+            lossy_utf8: false,
+            skip_lines: 0,
+            stride: 1,
+            cancel: None,
+            max_rows: None,
+            capacity: 0,
+            require_matches: false,
+            fail_on_read_errors: false,
+            number_format: None,
+            missing_policy: MissingPolicy::KeepNaN,
+            column_missing_policy: HashMap::new(),
+            #[cfg(feature = "rayon")]
+            parallel: false,
+            value_type: PhantomData,
+        }
+    }
+}
+
+impl<T> Extractor<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a regex extracting one or more columns.
+    pub fn with_data_regex(mut self, regex: NamedRegex) -> Self {
+        let regex = self.apply_number_format(regex);
+        self.data_regex.push(regex);
+        self
+    }
+
+    /// Adds a context regex, e.g. for section headers like `=== Run 42 ===`.
+    ///
+    /// Context regexes are matched against every line, including the ones which are filtered out.
+    /// Their columns are appended after the data columns and keep the value of the last match
+    /// (NaN before the first match) for all following rows.
+    pub fn with_context_regex(mut self, regex: NamedRegex) -> Self {
+        let regex = self.apply_number_format(regex);
+        self.context_regex.push(regex);
+        self
+    }
+
+    /// Data is only extracted from lines matching at least one of the included expressions.
+    /// All lines are used if no expression is included.
+    pub fn include(mut self, regex: Regex) -> Self {
+        self.included_lines.push(regex);
+        self
+    }
+
+    /// Data is not extracted from lines matching one of the excluded expressions.
+    pub fn exclude(mut self, regex: Regex) -> Self {
+        self.excluded_lines.push(regex);
+        self
+    }
+
+    /// Uses the column with the given name as base data ('x-axis').
+    pub fn base_data(mut self, name: &str) -> Self {
+        self.base_data_name = Some(name.into());
+        self
+    }
+
+    /// Uses the first group of positional regexes instead of the full match.
+    pub fn group_mode(mut self, group: bool) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// Matches the data regexes of chunks of lines on the rayon thread pool.
+    /// The order of the rows is preserved.
+    #[cfg(feature = "rayon")]
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Treats a line matching `regex` as the start of a record spanning all lines up to the next
+    /// record start. Filters and data regexes are applied to the whole record, with its lines joined
+    /// by `\n`; use `(?m)` or `(?s)` in expressions which have to match across lines.
+    pub fn record_start(mut self, regex: Regex) -> Self {
+        self.record_start = Some(regex);
+        self
+    }
+
+    /// Only extracts data after the first line matching `regex`, the line itself excluded.
+    ///
+    /// Unlike the include and exclude filters, this bounds a whole region of the input,
+    /// e.g. a single phase of a process. Context regexes still see the lines before the region.
+    pub fn start_marker(mut self, regex: Regex) -> Self {
+        self.start_marker = Some(regex);
+        self
+    }
+
+    /// Stops the extraction at the first line inside the region matching `regex`, see `start_marker`.
+    pub fn stop_marker(mut self, regex: Regex) -> Self {
+        self.stop_marker = Some(regex);
+        self
+    }
+
+    /// Ignores the first `lines` lines of the input, like banners or headers.
+    /// They are skipped before filtering and are not seen by context regexes.
+    pub fn skip_lines(mut self, lines: usize) -> Self {
+        self.skip_lines = lines;
+        self
+    }
+
+    /// Only extracts data from every `stride`th line passing the include and exclude filters,
+    /// starting with the first one. Context regexes still see all lines.
+    pub fn sample_every(mut self, stride: usize) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    /// Replaces invalid UTF-8 sequences by `U+FFFD` and still processes the affected lines,
+    /// instead of skipping them. The number of affected lines is available from [`ExtractIter::lossy_lines`].
+    pub fn lossy_utf8(mut self, lossy: bool) -> Self {
+        self.lossy_utf8 = lossy;
+        self
+    }
+
+    /// Fails the extraction with `ExtractionError::NoMatches` if a data regex did not match any line,
+    /// e.g. because of a typo. Checked once the input is exhausted.
+    pub fn require_matches(mut self, require: bool) -> Self {
+        self.require_matches = require;
+        self
+    }
+
+    /// Fails [`Extractor::run`], [`Extractor::run_multi`] and [`Extractor::run_async`] with
+    /// `ExtractionError::ReadError` at the first line which cannot be read, instead of skipping it.
+    /// Invalid UTF-8 can be replaced with [`Extractor::lossy_utf8`] instead.
+    pub fn fail_on_read_errors(mut self, fail: bool) -> Self {
+        self.fail_on_read_errors = fail;
+        self
+    }
+
+    /// Stops the extraction once `max_rows` rows were extracted, e.g. to preview huge inputs.
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Pre-allocates the table for about `rows` rows, e.g. estimated from the input size,
+    /// to avoid reallocations on large inputs. The table still grows beyond it if needed.
+    pub fn capacity_hint(mut self, rows: usize) -> Self {
+        self.capacity = rows;
+        self
+    }
+
+    pub(crate) fn is_full(&self, rows: usize) -> bool {
+        self.max_rows.is_some_and(|max| rows >= max)
+    }
+
+    /// Aborts the extraction once `token` is set, which is checked before every line is read.
+    ///
+    /// The rows extracted so far are kept: `run` returns a partial `DataTable` and iterators
+    /// yield `ExtractionError::Cancelled` after their last row. Rows of lines read before,
+    /// like the current chunk in parallel mode, are still extracted.
+    pub fn cancel_token(mut self, token: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|c| c.load(Ordering::Relaxed))
+    }
+
+    /// Sets the decimal and thousands separators of all regexes which don't have their own number format,
+    /// including the ones added later.
+    pub fn number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = Some(number_format);
+        self.data_regex = std::mem::take(&mut self.data_regex)
+            .into_iter()
+            .map(|r| self.apply_number_format(r))
+            .collect();
+        self.context_regex = std::mem::take(&mut self.context_regex)
+            .into_iter()
+            .map(|r| self.apply_number_format(r))
+            .collect();
+        self
+    }
+
+    fn apply_number_format(&self, mut regex: NamedRegex) -> NamedRegex {
+        if regex.number_format.is_none() {
+            regex.number_format = self.number_format;
+        }
+        regex
+    }
+
+    /// Sets how missing values of all numeric columns are handled.
+    pub fn missing_policy(mut self, policy: MissingPolicy) -> Self {
+        self.missing_policy = policy;
+        self
+    }
+
+    /// Sets how missing values of the numeric column `name` are handled,
+    /// overriding the policy set with [`Extractor::missing_policy`].
+    pub fn column_missing_policy(mut self, name: &str, policy: MissingPolicy) -> Self {
+        self.column_missing_policy.insert(name.into(), policy);
+        self
+    }
+
+    /// Returns the names of the extracted numeric columns.
+    pub fn column_names(&self) -> Vec<String> {
+        self.data_regex
+            .iter()
+            .chain(&self.context_regex)
+            .filter(|r| !r.is_text())
+            .flat_map(NamedRegex::column_names)
+            .collect()
+    }
+
+    /// Returns the names of the extracted text columns.
+    pub fn text_column_names(&self) -> Vec<String> {
+        self.data_regex
+            .iter()
+            .chain(&self.context_regex)
+            .filter(|r| r.is_text())
+            .flat_map(NamedRegex::column_names)
+            .collect()
+    }
+}
+
+impl<T> Extractor<T>
+where
+    T: Numeric,
+{
+    /// Extracts all data from `reader` into a `DataTable`.
+    ///
+    /// Lines which cannot be read are skipped, see [`Extractor::fail_on_read_errors`].
+    pub fn run<Reader: Read>(&self, reader: Reader) -> Result<DataTable<T>, ExtractionError> {
+        self.build_table(&mut self.iter(reader))
+    }
+
+    /// Extracts all data from `reader` into a `DataTable`, like [`Extractor::run`], and returns it
+    /// along with the warnings explaining missing values, like unparseable values, lines which
+    /// could not be read and data regexes which never matched.
+    pub fn run_with_warnings<Reader: Read>(
+        &self,
+        reader: Reader,
+    ) -> Result<(DataTable<T>, Vec<Warning>), ExtractionError> {
+        let mut rows = self.iter(reader).with_warnings();
+        let table = self.build_table(&mut rows)?;
+        Ok((table, rows.take_warnings()))
+    }
+
+    fn build_table(
+        &self,
+        rows: impl Iterator<Item = Result<Row<T>, ExtractionError>>,
+    ) -> Result<DataTable<T>, ExtractionError> {
+        let mut builder = DataTableBuilder::new(&self.column_names())?
+            .with_text_columns(&self.text_column_names())?
+            .with_capacity(self.capacity);
+
+        for row in rows {
+            let row = match row {
+                Ok(row) => row,
+                Err(ExtractionError::ReadError { .. }) if !self.fail_on_read_errors => continue,
+                Err(ExtractionError::Cancelled) => break,
+                Err(e) => return Err(e),
+            };
+            builder.add_row(row.values(), row.texts())?;
+        }
+
+        self.finish_table(builder)
+    }
+
+    /// Builds the table and sets the metadata of the extracted columns.
+    fn finish_table(&self, builder: DataTableBuilder<T>) -> Result<DataTable<T>, ExtractionError> {
+        let mut table = builder.build(self.base_data_name.as_deref())?;
+        for regex in self.data_regex.iter().chain(&self.context_regex) {
+            let meta = regex.column_meta();
+            for name in regex.column_names() {
+                table.set_column_meta(&name, meta.clone())?;
+            }
+        }
+        Ok(table)
+    }
+
+    /// Extracts all data from several named inputs, like rotated log files, into one `DataTable`.
+    ///
+    /// The inputs are processed one after another, each starting without context or previous values.
+    /// The name of the input each row came from is stored in the text column `source_column`.
+    /// The row limit and [`Extractor::require_matches`] apply to all inputs together, so a data regex only
+    /// has to match in one of them. Lines which cannot be read are skipped, see [`Extractor::fail_on_read_errors`].
+    pub fn run_multi<Reader: Read>(
+        &self,
+        inputs: Vec<(String, Reader)>,
+        source_column: &str,
+    ) -> Result<DataTable<T>, ExtractionError> {
+        let mut text_names = self.text_column_names();
+        text_names.push(source_column.into());
+        let mut builder = DataTableBuilder::new(&self.column_names())?
+            .with_text_columns(&text_names)?
+            .with_capacity(self.capacity);
+
+        let mut per_input = self.clone().require_matches(false);
+        per_input.max_rows = None;
+        let mut extracted = 0;
+        // The data regexes which did not match in any input so far, `None` before the first one.
+        let mut unmatched: Option<Vec<String>> = None;
+        for (source, reader) in inputs {
+            let mut rows = per_input.iter(reader);
+            for row in &mut rows {
+                if self.is_full(extracted) {
+                    return self.finish_table(builder);
+                }
+                let row = match row {
+                    Ok(row) => row,
+                    Err(ExtractionError::ReadError { .. }) if !self.fail_on_read_errors => continue,
+                    Err(ExtractionError::Cancelled) => return self.finish_table(builder),
+                    Err(e) => return Err(e),
+                };
+                let mut texts = row.texts().to_vec();
+                texts.push(source.clone());
+                builder.add_row(row.values(), &texts)?;
+                extracted += 1;
+            }
+            let missing = rows.unmatched();
+            unmatched = Some(match unmatched {
+                None => missing.map(String::from).collect(),
+                Some(before) => {
+                    let missing: Vec<_> = missing.collect();
+                    before
+                        .into_iter()
+                        .filter(|regex| missing.contains(&regex.as_str()))
+                        .collect()
+                }
+            });
+        }
+
+        match unmatched.and_then(|regexes| regexes.into_iter().next()) {
+            Some(regex) if self.require_matches => Err(ExtractionError::NoMatches { regex }),
+            _ => self.finish_table(builder),
+        }
+    }
+
+    /// Extracts all data from an asynchronous `reader` into a `DataTable`.
+    ///
+    /// Lines which cannot be read are skipped, see [`Extractor::fail_on_read_errors`].
+    #[cfg(feature = "async")]
+    pub async fn run_async<Reader>(&self, reader: Reader) -> Result<DataTable<T>, ExtractionError>
+    where
+        Reader: tokio::io::AsyncRead + Unpin,
+    {
+        let mut builder = DataTableBuilder::new(&self.column_names())?
+            .with_text_columns(&self.text_column_names())?
+            .with_capacity(self.capacity);
+        let mut lines = self.filter_lines_async(reader);
+        let mut processor = LineProcessor::new(self);
+        let mut rows = std::collections::VecDeque::new();
+        let (mut extracted, mut done) = (0, false);
+        while !done && !self.is_cancelled() {
+            match lines
+                .next_with(|line| processor.update_context(line, self))
+                .await
+            {
+                Some(line) => processor.process(self, lines.line_number(), line, &mut rows),
+                None => {
+                    let read = (lines.lines_read(), lines.lossy_lines());
+                    processor.finish(self, read, extracted, &mut rows);
+                    done = true;
+                }
+            }
+            for row in rows.drain(..) {
+                if self.is_full(extracted) {
+                    return self.finish_table(builder);
+                }
+                let row = match row {
+                    Ok(row) => row,
+                    Err(ExtractionError::ReadError { .. }) if !self.fail_on_read_errors => continue,
+                    Err(e) => return Err(e),
+                };
+                builder.add_row(&row.values, &row.texts)?;
+                extracted += 1;
+            }
+        }
+
+        self.finish_table(builder)
+    }
+
+    /// Keeps reading from `reader` while it is being written to, like `tail -f`, and passes every
+    /// extracted row to `on_row`.
+    ///
+    /// The input is read from its current position; seek to the end first to only process new lines.
+    /// Returns once `on_row` returns `ControlFlow::Break`, which is checked whenever a row was extracted,
+    /// or once the extraction was cancelled, even while waiting for new lines. Lines which cannot be read
+    /// are skipped.
+    pub fn follow<Reader, F>(
+        &self,
+        reader: Reader,
+        poll_interval: Duration,
+        mut on_row: F,
+    ) -> Result<(), ExtractionError>
+    where
+        Reader: Read,
+        F: FnMut(Row<T>) -> ControlFlow<()>,
+    {
+        let mut reader = FollowReader::new(reader, poll_interval);
+        if let Some(token) = &self.cancel {
+            reader = reader.with_cancel_token(token.clone());
+        }
+        for row in self.iter(reader) {
+            match row {
+                Ok(row) => {
+                    if on_row(row).is_break() {
+                        return Ok(());
+                    }
+                }
+                Err(ExtractionError::ReadError { .. }) => continue,
+                Err(ExtractionError::Cancelled) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts how many of the first `max_lines` lines of `reader` each expression matches on its own,
+    /// without extracting any data, e.g. to check a configuration before running it on a large input.
+    ///
+    /// The lines are neither joined into records nor limited by the start and stop markers,
+    /// invalid UTF-8 is replaced.
+    pub fn count_matches<Reader: Read>(
+        &self,
+        reader: Reader,
+        max_lines: usize,
+    ) -> Result<MatchCounts, ExtractionError> {
+        let mut expressions: Vec<(ExpressionKind, String, &Regex)> = vec![];
+        for regex in &self.data_regex {
+            let kind = if regex.is_text() {
+                ExpressionKind::Text
+            } else {
+                ExpressionKind::Data
+            };
+            expressions.push((kind, regex.name.clone(), &regex.regex));
+        }
+        for regex in &self.context_regex {
+            expressions.push((ExpressionKind::Context, regex.name.clone(), &regex.regex));
+        }
+        let filters = self
+            .included_lines
+            .iter()
+            .map(|r| (ExpressionKind::Include, r))
+            .chain(
+                self.excluded_lines
+                    .iter()
+                    .map(|r| (ExpressionKind::Exclude, r)),
+            )
+            .chain(
+                self.record_start
+                    .iter()
+                    .map(|r| (ExpressionKind::RecordStart, r)),
+            )
+            .chain(
+                self.start_marker
+                    .iter()
+                    .map(|r| (ExpressionKind::StartMarker, r)),
+            )
+            .chain(
+                self.stop_marker
+                    .iter()
+                    .map(|r| (ExpressionKind::StopMarker, r)),
+            );
+        for (kind, regex) in filters {
+            expressions.push((kind, regex.as_str().to_string(), regex));
+        }
+
+        let matches = |regex: &Regex, line: &str| regex.is_match(line).unwrap_or(false);
+        let mut counts = vec![0; expressions.len()];
+        let (mut lines, mut passed) = (0, 0);
+        let input = FilterIter::new(reader, vec![], vec![])
+            .with_lossy_utf8(true)
+            .with_max_lines(max_lines);
+        for line in input {
+            let line = line.map_err(|source| ExtractionError::ReadError {
+                line: lines + 1,
+                source,
+            })?;
+            lines += 1;
+            for (count, (_, _, regex)) in counts.iter_mut().zip(&expressions) {
+                if matches(regex, &line) {
+                    *count += 1;
+                }
+            }
+            let included = self.included_lines.is_empty()
+                || self.included_lines.iter().any(|r| matches(r, &line));
+            if included && !self.excluded_lines.iter().any(|r| matches(r, &line)) {
+                passed += 1;
+            }
+        }
+
+        Ok(MatchCounts {
+            lines,
+            passed,
+            expressions: expressions
+                .into_iter()
+                .zip(counts)
+                .map(|((kind, name, regex), lines)| ExpressionMatches {
+                    kind,
+                    name,
+                    pattern: regex.as_str().to_string(),
+                    lines,
+                })
+                .collect(),
+        })
+    }
+
+    /// Lazily extracts rows from `reader`, see [`crate::extract_iter`].
+    pub fn iter<Reader: Read>(&self, reader: Reader) -> ExtractIter<Reader, T> {
+        ExtractIter::new(reader, self.clone())
+    }
+
+    /// Returns the lines or records of `reader` data would be extracted from, without extracting it.
+    ///
+    /// The lines pass the include and exclude expressions, skipped lines, markers and sampling configured here.
+    pub fn filter_lines<Reader: Read>(&self, reader: Reader) -> FilterIter<Reader> {
+        FilterIter::new(
+            reader,
+            self.included_lines.clone(),
+            self.excluded_lines.clone(),
+        )
+        .with_record_start(self.record_start.clone())
+        .with_lossy_utf8(self.lossy_utf8)
+        .with_skip_lines(self.skip_lines)
+        .with_region(self.start_marker.clone(), self.stop_marker.clone())
+        .with_stride(self.stride)
+    }
+
+    /// Asynchronous counterpart of [`Extractor::filter_lines`].
+    #[cfg(feature = "async")]
+    pub fn filter_lines_async<Reader>(&self, reader: Reader) -> AsyncFilterIter<Reader>
+    where
+        Reader: tokio::io::AsyncRead + Unpin,
+    {
+        AsyncFilterIter::new(
+            reader,
+            self.included_lines.clone(),
+            self.excluded_lines.clone(),
+        )
+        .with_record_start(self.record_start.clone())
+        .with_lossy_utf8(self.lossy_utf8)
+        .with_skip_lines(self.skip_lines)
+        .with_region(self.start_marker.clone(), self.stop_marker.clone())
+        .with_stride(self.stride)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::NamedRegex;
+
+    const LOG: &str = "t=1 h=5\nt=2\nt=3 h=7\n";
+
+    fn extractor() -> Extractor<f64> {
+        Extractor::new()
+            .with_data_regex(NamedRegex::try_new("t", r"t=(\d+)").unwrap())
+            .with_data_regex(NamedRegex::try_new("h", r"h=(\d+)").unwrap())
+            .group_mode(true)
+    }
+
+    fn column(table: &DataTable<f64>, name: &str) -> Vec<f64> {
+        table.col_slice_by_name(name).unwrap().to_vec()
+    }
+
+    #[test]
+    fn missing_policies() {
+        let run = |extractor: Extractor<f64>| extractor.run(LOG.as_bytes());
+
+        let kept = run(extractor()).unwrap();
+        assert!(column(&kept, "h")[1].is_nan());
+        let skipped = run(extractor().missing_policy(MissingPolicy::SkipRow)).unwrap();
+        assert_eq!(column(&skipped, "t"), [1.0, 3.0]);
+        let filled = run(extractor().missing_policy(MissingPolicy::ForwardFill)).unwrap();
+        assert_eq!(column(&filled, "h"), [5.0, 5.0, 7.0]);
+        let zeroed = run(extractor().column_missing_policy("h", MissingPolicy::Zero)).unwrap();
+        assert_eq!(column(&zeroed, "h"), [5.0, 0.0, 7.0]);
+        assert!(matches!(
+            run(extractor().missing_policy(MissingPolicy::Error)),
+            Err(ExtractionError::MissingValue { column, line: 2, .. }) if column == "h"
+        ));
+    }
+
+    #[test]
+    fn required_columns_merge_lines_into_rows() {
+        let table: DataTable<f64> = Extractor::new()
+            .with_data_regex(
+                NamedRegex::try_new("temp", r"temp=(\d+)")
+                    .unwrap()
+                    .with_required(true),
+            )
+            .with_data_regex(
+                NamedRegex::try_new("bar", r"bar=(\d+)")
+                    .unwrap()
+                    .with_required(true),
+            )
+            .group_mode(true)
+            .run("temp=20\nbar=1\ntemp=21\nnoise\nbar=2\ntemp=22\n".as_bytes())
+            .unwrap();
+        assert_eq!(column(&table, "temp"), [20.0, 21.0]);
+        assert_eq!(column(&table, "bar"), [1.0, 2.0]);
+    }
+
+    #[test]
+    fn records_are_matched_as_a_whole() {
+        let table: DataTable<f64> = Extractor::new()
+            .with_data_regex(NamedRegex::try_new("a", r"a=(\d+)").unwrap())
+            .with_data_regex(NamedRegex::try_new("b", r"b=(\d+)").unwrap())
+            .record_start(Regex::new("^BEGIN").unwrap())
+            .include(Regex::new("(?m)^ok").unwrap())
+            .group_mode(true)
+            .run("BEGIN\na=1\nok\nb=2\nBEGIN\na=3\nb=4\nBEGIN\nok\na=5\nb=6".as_bytes())
+            .unwrap();
+        assert_eq!(column(&table, "a"), [1.0, 5.0]);
+        assert_eq!(column(&table, "b"), [2.0, 6.0]);
+    }
+
+    #[test]
+    fn require_matches_reports_unmatched_regex() {
+        let result = extractor()
+            .with_data_regex(NamedRegex::try_new("p", r"p=(\d+)").unwrap())
+            .require_matches(true)
+            .run(LOG.as_bytes());
+        assert!(matches!(result, Err(ExtractionError::NoMatches { regex }) if regex == "p"));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn run_async_extracts_like_run() {
+        let extractor = extractor()
+            .with_context_regex(NamedRegex::try_new("section", r"^\[(\d+)\]").unwrap())
+            .exclude(Regex::new("^#").unwrap());
+        let log = "[1]\nt=1 h=5\n# t=9\n[2]\nt=2\nt=3 h=7\n";
+        let table = extractor.run_async(log.as_bytes()).await.unwrap();
+        assert!(table.approx_eq(&extractor.run(log.as_bytes()).unwrap(), 0.0));
+        assert_eq!(column(&table, "section"), [1.0, 1.0, 2.0, 2.0, 2.0]);
+
+        let result = extractor
+            .with_data_regex(NamedRegex::try_new("p", r"p=(\d+)").unwrap())
+            .require_matches(true)
+            .run_async(LOG.as_bytes())
+            .await;
+        assert!(matches!(result, Err(ExtractionError::NoMatches { regex }) if regex == "p"));
+    }
+
+    #[test]
+    fn follow_stops_on_an_idle_input() {
+        let interval = Duration::from_millis(5);
+        let mut rows = 0;
+        let result = extractor().follow(LOG.as_bytes(), interval, |_| {
+            rows += 1;
+            ControlFlow::Break(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(rows, 1);
+
+        // `LOG` is exhausted long before the token is set, so this only returns if it is checked while idle.
+        let token = Arc::new(AtomicBool::new(false));
+        let cancel = token.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            cancel.store(true, Ordering::Relaxed);
+        });
+        let mut values = vec![];
+        let result = extractor()
+            .cancel_token(token)
+            .require_matches(true)
+            .follow(LOG.as_bytes(), interval, |row| {
+                values.push(row.values()[0]);
+                ControlFlow::Continue(())
+            });
+        handle.join().unwrap();
+        assert!(result.is_ok());
+        assert_eq!(values, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn run_multi_limits_all_inputs_together() {
+        let inputs = || {
+            vec![
+                (
+                    "a.log".to_string(),
+                    "t=1
+t=2
+"
+                    .as_bytes(),
+                ),
+                (
+                    "b.log".to_string(),
+                    "t=3 h=7
+t=4
+"
+                    .as_bytes(),
+                ),
+            ]
+        };
+        let table = extractor()
+            .require_matches(true)
+            .run_multi(inputs(), "source")
+            .unwrap();
+        assert_eq!(column(&table, "t"), [1.0, 2.0, 3.0, 4.0]);
+        let sources: Vec<_> = table.get_text_col_by_name("source").unwrap().collect();
+        assert_eq!(sources, ["a.log", "a.log", "b.log", "b.log"]);
+
+        let table = extractor()
+            .max_rows(3)
+            .run_multi(inputs(), "source")
+            .unwrap();
+        assert_eq!(column(&table, "t"), [1.0, 2.0, 3.0]);
+
+        let result = extractor()
+            .with_data_regex(NamedRegex::try_new("p", r"p=(\d+)").unwrap())
+            .require_matches(true)
+            .run_multi(inputs(), "source");
+        assert!(matches!(result, Err(ExtractionError::NoMatches { regex }) if regex == "p"));
+    }
+
+    #[test]
+    fn unreadable_lines_are_skipped_unless_they_fail() {
+        let input: &[u8] = b"t=1\n\xff\nt=2\n";
+        let table = extractor().run(input).unwrap();
+        assert_eq!(column(&table, "t"), [1.0, 2.0]);
+        assert!(matches!(
+            extractor().fail_on_read_errors(true).run(input),
+            Err(ExtractionError::ReadError { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn warnings_are_collected_alongside_the_table() {
+        let extractor = extractor()
+            .with_data_regex(NamedRegex::try_new("p", r"p=(\d+)").unwrap())
+            .with_data_regex(NamedRegex::try_new("x", r"x=(\S+)").unwrap());
+        let (table, warnings) = extractor
+            .run_with_warnings(&b"t=1 x=a\n\xff\nt=3\n"[..])
+            .unwrap();
+        assert_eq!(column(&table, "t"), [1.0, 3.0]);
+        assert!(matches!(&warnings[..], [
+            Warning::UnparseableValue { column, line: 1, text },
+            Warning::ReadError { line: 2, .. },
+            Warning::NeverMatched { regex: h },
+            Warning::NeverMatched { regex: p },
+        ] if column == "x" && text == "a" && h == "h" && p == "p"));
+    }
+
+    #[test]
+    fn lossy_utf8_keeps_the_affected_lines() {
+        let input = &b"t=1 \xff\nt=2\nt=3 \xfe h=4\n"[..];
+        let mut rows = extractor().lossy_utf8(true).iter(input);
+        let t: Vec<_> = rows.by_ref().map(|row| row.unwrap().values()[0]).collect();
+        assert_eq!(t, [1.0, 2.0, 3.0]);
+        assert_eq!(rows.lossy_lines(), 2);
+    }
+
+    #[test]
+    fn max_rows_stops_the_extraction() {
+        let table = extractor().max_rows(2).run(LOG.as_bytes()).unwrap();
+        assert_eq!(column(&table, "t"), [1.0, 2.0]);
+        let rows: Vec<_> = extractor().max_rows(0).iter(LOG.as_bytes()).collect();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn skipped_lines_are_not_extracted() {
+        let input = format!("t=9 h=9\n{}", LOG);
+        let table = extractor().skip_lines(2).run(input.as_bytes()).unwrap();
+        assert_eq!(column(&table, "t"), [2.0, 3.0]);
+        let table = extractor().skip_lines(10).run(input.as_bytes()).unwrap();
+        assert!(column(&table, "t").is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_extraction_keeps_the_line_order() {
+        let input: String = (0..10_000)
+            .map(|i| {
+                if i % 3 == 0 {
+                    format!("t={}\n", i)
+                } else {
+                    format!("t={} h={}\n", i, i)
+                }
+            })
+            .collect();
+        let sequential = extractor().run(input.as_bytes()).unwrap();
+        let parallel = extractor().parallel(true).run(input.as_bytes()).unwrap();
+        assert!(parallel.approx_eq(&sequential, 0.0));
+        assert_eq!(column(&parallel, "t")[9_999], 9_999.0);
+    }
+
+    #[test]
+    fn cancelled_extractions_keep_the_rows_so_far() {
+        let token = Arc::new(AtomicBool::new(false));
+        let mut rows = extractor().cancel_token(token.clone()).iter(LOG.as_bytes());
+        assert_eq!(rows.next().unwrap().unwrap().values()[0], 1.0);
+        token.store(true, Ordering::Relaxed);
+        assert!(matches!(rows.next(), Some(Err(ExtractionError::Cancelled))));
+        assert!(rows.next().is_none());
+
+        let table = extractor()
+            .cancel_token(token)
+            .require_matches(true)
+            .run(LOG.as_bytes())
+            .unwrap();
+        assert!(column(&table, "t").is_empty());
+    }
+
+    #[test]
+    fn sampling_keeps_every_nth_line() {
+        let input = "t=1\nidle\nt=2\nt=3\nt=4\n";
+        let only_t = || extractor().include(Regex::new("t=").unwrap());
+        let table = only_t().sample_every(2).run(input.as_bytes()).unwrap();
+        assert_eq!(column(&table, "t"), [1.0, 3.0]);
+        let table = only_t().sample_every(1).run(input.as_bytes()).unwrap();
+        assert_eq!(column(&table, "t"), [1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn markers_bound_the_extracted_region() {
+        let input = "t=1\nstart t=2\nt=3\nt=4\nstop t=5\nstart\nt=6\n";
+        let table = extractor()
+            .start_marker(Regex::new("^start").unwrap())
+            .stop_marker(Regex::new("^stop").unwrap())
+            .run(input.as_bytes())
+            .unwrap();
+        assert_eq!(column(&table, "t"), [3.0, 4.0]);
+        let table = extractor()
+            .stop_marker(Regex::new("^stop").unwrap())
+            .run(input.as_bytes())
+            .unwrap();
+        assert_eq!(column(&table, "t"), [1.0, 2.0, 3.0, 4.0]);
+    }
+}
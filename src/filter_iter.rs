@@ -1,11 +1,99 @@
-use std::io::{BufRead, BufReader, Lines, Read};
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Read},
+    ops::Range,
+};
 
 use fancy_regex::Regex;
 
-pub(crate) struct FilterIter<Reader> {
-    lines: Lines<BufReader<Reader>>,
-    includes: Vec<Regex>,
-    excludes: Vec<Regex>,
+use crate::prefilter::Prefilter;
+
+/// A record passing the filters with its position in the input, see [`FilterIter::with_positions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilteredLine {
+    /// The 1-based number of the line, or of the first line of a multiline record.
+    pub line_number: usize,
+    pub text: String,
+    /// The index of the first include expression matching the text, `None` for context lines,
+    /// inverted filtering or without include expressions.
+    pub pattern_index: Option<usize>,
+    /// The byte range of the match of that expression within `text`, after replacements.
+    pub span: Option<Range<usize>>,
+}
+
+/// How [`FilterIter::filter_between`] treats the start and end markers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BetweenOptions {
+    /// Also passes the records matching the markers, like awk's `/start/,/end/`.
+    /// A record matching both markers then forms a region of its own, otherwise the end marker
+    /// is only looked for from the record after the start.
+    pub inclusive: bool,
+    /// Passes every region instead of stopping at the end of the first one.
+    pub repeat: bool,
+}
+
+/// An iterator over the records passing the filters with their positions, see [`FilterIter::with_positions`].
+pub struct FilteredLines<Reader> {
+    lines: FilterIter<Reader>,
+}
+
+impl<Reader: Read> Iterator for FilteredLines<Reader> {
+    type Item = io::Result<FilteredLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = match self.lines.next()? {
+            Ok(text) => text,
+            Err(e) => return Some(Err(e)),
+        };
+        let matched = if self.lines.invert {
+            None
+        } else {
+            self.lines.includes.first_match(&text)
+        };
+        let (pattern_index, span) = matched.unzip();
+        Some(Ok(FilteredLine {
+            line_number: self.lines.line_number(),
+            text,
+            pattern_index,
+            span,
+        }))
+    }
+}
+
+/// An iterator over the lines of an input passing include and exclude filters, see [`crate::filter_iter`].
+///
+/// Lines which cannot be read are yielded as errors, reading continues with the next line.
+pub struct FilterIter<Reader> {
+    reader: BufReader<Reader>,
+    decoder: LineDecoder,
+    skip: usize,
+    stride: Stride,
+    region: Region,
+    includes: Prefilter,
+    excludes: Prefilter,
+    records: Option<RecordJoiner>,
+    lines_read: usize,
+    line_number: usize,
+    invert: bool,
+    before_context: usize,
+    after_context: usize,
+    /// The last records filtered out, up to `before_context` of them.
+    preceding: VecDeque<(usize, String)>,
+    /// The number of records filtered out which are still returned after the last record passing the filters.
+    following: usize,
+    /// The records to return next, with the numbers of their first lines.
+    pending: VecDeque<(usize, String)>,
+    max_lines: Option<usize>,
+    /// The number of records kept from the end of the input, until it is exhausted.
+    last_n: Option<usize>,
+    /// The last records passing the filters while the input is read for `last_n`.
+    tail: VecDeque<(usize, String)>,
+    returned: usize,
+    replacements: Vec<(Regex, String)>,
+    /// The group of the include matches returned instead of the records.
+    only_matching: Option<usize>,
+    dedup: Option<Dedup>,
 }
 
 impl<Reader> FilterIter<Reader>
@@ -13,12 +101,315 @@ where
     Reader: Read,
 {
     pub fn new(reader: Reader, included_lines: Vec<Regex>, excluded_lines: Vec<Regex>) -> Self {
-        let buffer = std::io::BufReader::new(reader);
-        let lines = buffer.lines();
         Self {
-            lines,
-            includes: included_lines,
-            excludes: excluded_lines,
+            reader: BufReader::new(reader),
+            decoder: LineDecoder::default(),
+            skip: 0,
+            stride: Stride::default(),
+            region: Region::default(),
+            includes: Prefilter::new(&included_lines),
+            excludes: Prefilter::new(&excluded_lines),
+            records: None,
+            lines_read: 0,
+            line_number: 0,
+            invert: false,
+            before_context: 0,
+            after_context: 0,
+            preceding: VecDeque::new(),
+            following: 0,
+            pending: VecDeque::new(),
+            max_lines: None,
+            last_n: None,
+            tail: VecDeque::new(),
+            returned: 0,
+            replacements: vec![],
+            only_matching: None,
+            dedup: None,
+        }
+    }
+
+    /// Joins lines into records, each starting with a line matching `record_start`.
+    pub fn with_record_start(mut self, record_start: Option<Regex>) -> Self {
+        self.records = record_start.map(RecordJoiner::new);
+        self
+    }
+
+    /// Replaces invalid UTF-8 sequences by `U+FFFD` instead of failing to read the line.
+    pub fn with_lossy_utf8(mut self, lossy: bool) -> Self {
+        self.decoder.lossy = lossy;
+        self
+    }
+
+    /// Splits the input into lines at `delimiter` instead of `\n`, e.g. `"\0"` for the output of `find -print0`
+    /// or a record separator of a device. An empty delimiter keeps `\n`.
+    pub fn with_delimiter(mut self, delimiter: impl AsRef<[u8]>) -> Self {
+        let delimiter = delimiter.as_ref();
+        if !delimiter.is_empty() {
+            self.decoder.delimiter = delimiter.to_vec();
+        }
+        self
+    }
+
+    /// Skips the first `lines` lines of the input, before joining records and filtering.
+    pub fn with_skip_lines(mut self, lines: usize) -> Self {
+        self.skip = lines;
+        self
+    }
+
+    /// Only passes the records after the first one matching `start` and stops at the next one matching `stop`.
+    pub fn with_region(mut self, start: Option<Regex>, stop: Option<Regex>) -> Self {
+        self.region = Region::new(start, stop);
+        self
+    }
+
+    /// Only passes the records in the regions from a record matching `start` to the next one matching `end`,
+    /// e.g. to carve sections out of structured logs. The include and exclude expressions apply within the regions.
+    ///
+    /// This replaces a region set by [`FilterIter::with_region`].
+    pub fn filter_between(mut self, start: Regex, end: Regex, options: BetweenOptions) -> Self {
+        self.region = Region::between(start, end, options);
+        self
+    }
+
+    /// Only keeps every `stride`th record passing the filters.
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = Stride::new(stride);
+        self
+    }
+
+    /// Only passes the records matching none of the include expressions, like `grep -v`.
+    /// The exclude expressions still apply.
+    pub fn with_invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Also returns up to `lines` records preceding each record passing the filters, like `grep -B`.
+    pub fn with_before_context(mut self, lines: usize) -> Self {
+        self.before_context = lines;
+        self
+    }
+
+    /// Also returns up to `lines` records following each record passing the filters, like `grep -A`.
+    pub fn with_after_context(mut self, lines: usize) -> Self {
+        self.after_context = lines;
+        self
+    }
+
+    /// Stops after returning `lines` records, like `head -n`, without reading the rest of the input.
+    pub fn with_max_lines(mut self, lines: usize) -> Self {
+        self.max_lines = Some(lines);
+        self
+    }
+
+    /// Only returns the last `lines` records, like `tail -n`. The whole input is read before
+    /// the first record is returned, but only `lines` records are kept.
+    ///
+    /// Combined with [`FilterIter::with_max_lines`], the limit applies to these records.
+    pub fn with_last_lines(mut self, lines: usize) -> Self {
+        self.last_n = Some(lines);
+        self
+    }
+
+    /// Returns the text of `group` of each match of the first include expression matching a record
+    /// instead of the whole record, like `grep -o` for group 0. Each match is returned separately.
+    ///
+    /// Records passing the filters without a match, e.g. without include expressions or with inverted
+    /// filtering, are not returned. Context records are still returned whole.
+    pub fn with_only_matching(mut self, group: usize) -> Self {
+        self.only_matching = Some(group);
+        self
+    }
+
+    /// Collapses consecutive records which are equal into the first one, like `uniq`.
+    /// The parts of the records matching `ignore`, e.g. a timestamp prefix, are removed before comparing.
+    pub fn with_dedup_consecutive(mut self, ignore: Option<Regex>) -> Self {
+        self.dedup = Some(Dedup::new(ignore));
+        self
+    }
+
+    /// Replaces all matches of `regex` in the returned records by `replacement`, like `sed s/regex/replacement/g`,
+    /// e.g. to mask IP addresses. `replacement` may refer to groups like `$1` or `${name}`.
+    ///
+    /// Replacements are applied in the order they were added, after filtering.
+    pub fn with_replacement(mut self, regex: Regex, replacement: impl Into<String>) -> Self {
+        self.replacements.push((regex, replacement.into()));
+        self
+    }
+
+    /// Returns the records with their line numbers and include matches instead of bare strings,
+    /// e.g. to link back into the input.
+    pub fn with_positions(self) -> FilteredLines<Reader> {
+        FilteredLines { lines: self }
+    }
+
+    /// Returns the number of lines read so far, including the ones filtered out.
+    pub fn lines_read(&self) -> usize {
+        self.lines_read
+    }
+
+    /// Returns the number of lines containing invalid UTF-8 which were read lossily.
+    pub fn lossy_lines(&self) -> usize {
+        self.decoder.lossy_lines
+    }
+
+    /// Returns the 1-based number of the line last returned, the first line for records,
+    /// or of the line which could not be read.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    fn read_line(&mut self) -> Option<io::Result<String>> {
+        let line = self.decoder.read_line(&mut self.reader);
+        if line.is_some() {
+            self.lines_read += 1;
+        }
+        line
+    }
+
+    fn next_record(&mut self) -> Option<io::Result<String>> {
+        while self.skip > 0 {
+            self.skip -= 1;
+            if let Ok(0) = self
+                .decoder
+                .read_until_delimiter(&mut self.reader, &mut Vec::new())
+            {
+                return None;
+            }
+            self.lines_read += 1;
+        }
+        loop {
+            let line = self.read_line();
+            let Some(records) = &mut self.records else {
+                self.line_number = self.lines_read;
+                return line;
+            };
+            match line {
+                Some(Ok(line)) => {
+                    if let Some((number, record)) = records.push(line, self.lines_read) {
+                        self.line_number = number;
+                        return Some(Ok(record));
+                    }
+                }
+                Some(Err(e)) => {
+                    self.line_number = self.lines_read;
+                    return Some(Err(e));
+                }
+                None => {
+                    let (number, record) = records.finish()?;
+                    self.line_number = number;
+                    return Some(Ok(record));
+                }
+            }
+        }
+    }
+
+    /// Returns the next record passing the filters, calling `on_record` for every record read,
+    /// including the ones which are filtered out.
+    pub(crate) fn next_with(
+        &mut self,
+        mut on_record: impl FnMut(&str),
+    ) -> Option<std::io::Result<String>> {
+        if self.max_lines.is_some_and(|max| self.returned >= max) {
+            return None;
+        }
+        if let Some(last_n) = self.last_n {
+            loop {
+                match self.next_unique(&mut on_record) {
+                    Some(Ok(record)) => {
+                        if self.tail.len() == last_n {
+                            self.tail.pop_front();
+                        }
+                        if last_n > 0 {
+                            self.tail.push_back((self.line_number, record));
+                        }
+                    }
+                    Some(Err(e)) => return Some(Err(e)),
+                    None => break,
+                }
+            }
+            self.last_n = None;
+            self.pending = std::mem::take(&mut self.tail);
+        }
+        let record = self
+            .next_unique(on_record)?
+            .map(|record| self.replace(record));
+        if record.is_ok() {
+            self.returned += 1;
+        }
+        Some(record)
+    }
+
+    fn replace(&self, record: String) -> String {
+        self.replacements
+            .iter()
+            .fold(record, |record, (regex, replacement)| {
+                match regex.replace_all(&record, replacement.as_str()) {
+                    Cow::Borrowed(_) => record,
+                    Cow::Owned(replaced) => replaced,
+                }
+            })
+    }
+
+    fn next_unique(&mut self, mut on_record: impl FnMut(&str)) -> Option<std::io::Result<String>> {
+        loop {
+            let record = self.next_filtered(&mut on_record);
+            if let (Some(dedup), Some(Ok(line))) = (&mut self.dedup, &record) {
+                if dedup.is_repeat(line) {
+                    continue;
+                }
+            }
+            return record;
+        }
+    }
+
+    fn next_filtered(
+        &mut self,
+        mut on_record: impl FnMut(&str),
+    ) -> Option<std::io::Result<String>> {
+        loop {
+            if let Some((number, record)) = self.pending.pop_front() {
+                self.line_number = number;
+                return Some(Ok(record));
+            }
+            let next_line = self.next_record();
+
+            if let Some(Ok(line)) = next_line {
+                on_record(&line);
+                match self.region.check(&line) {
+                    RegionState::Before => continue,
+                    RegionState::Inside => {}
+                    RegionState::After => return None,
+                }
+                let included = if self.invert {
+                    !self.includes.is_match(&line)
+                } else {
+                    is_included(&line, &self.includes)
+                };
+                if included && !is_ignored(&line, &self.excludes) && self.stride.keep() {
+                    self.following = self.after_context;
+                    self.pending.extend(self.preceding.drain(..));
+                    match self.only_matching {
+                        Some(group) => {
+                            let number = self.line_number;
+                            let fragments = self.includes.fragments(&line, group);
+                            self.pending
+                                .extend(fragments.into_iter().map(|f| (number, f)));
+                        }
+                        None => self.pending.push_back((self.line_number, line)),
+                    }
+                } else if self.following > 0 {
+                    self.following -= 1;
+                    self.pending.push_back((self.line_number, line));
+                } else if self.before_context > 0 {
+                    if self.preceding.len() == self.before_context {
+                        self.preceding.pop_front();
+                    }
+                    self.preceding.push_back((self.line_number, line));
+                }
+            } else {
+                return next_line;
+            }
         }
     }
 }
@@ -30,38 +421,413 @@ where
     type Item = std::io::Result<String>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        self.next_with(|_| {})
+    }
+}
+
+pub(crate) fn is_ignored(line: &str, ignores: &Prefilter) -> bool {
+    ignores.is_match(line)
+}
+
+pub(crate) fn is_included(line: &str, includes: &Prefilter) -> bool {
+    includes.is_empty() || includes.is_match(line)
+}
+
+/// Converts the bytes of a line into a string without line ending.
+pub(crate) struct LineDecoder {
+    pub lossy: bool,
+    pub lossy_lines: usize,
+    /// The bytes ending a line, `\r\n` is accepted as well for the default `\n`.
+    pub delimiter: Vec<u8>,
+}
+
+impl Default for LineDecoder {
+    fn default() -> Self {
+        Self {
+            lossy: false,
+            lossy_lines: 0,
+            delimiter: b"\n".to_vec(),
+        }
+    }
+}
+
+impl LineDecoder {
+    pub fn read_line(&mut self, reader: &mut impl BufRead) -> Option<io::Result<String>> {
+        let mut line = Vec::new();
+        match self.read_until_delimiter(reader, &mut line) {
+            Ok(0) => None,
+            Ok(_) => Some(self.decode(line)),
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Appends the bytes up to and including the next delimiter to `line`, returning the number of bytes read.
+    pub fn read_until_delimiter(
+        &self,
+        reader: &mut impl BufRead,
+        line: &mut Vec<u8>,
+    ) -> io::Result<usize> {
+        let last = self.delimiter.last().copied().unwrap_or(b'\n');
+        let mut read = 0;
         loop {
-            let next_line = self.lines.next();
+            let n = reader.read_until(last, line)?;
+            read += n;
+            if n == 0 || line.ends_with(&self.delimiter) {
+                return Ok(read);
+            }
+        }
+    }
 
-            if let Some(Ok(line)) = next_line {
-                if !is_included(&line, &self.includes) {
-                    continue;
+    pub fn decode(&mut self, mut line: Vec<u8>) -> io::Result<String> {
+        if line.ends_with(&self.delimiter) {
+            line.truncate(line.len() - self.delimiter.len());
+            if self.delimiter == b"\n" && line.ends_with(b"\r") {
+                line.pop();
+            }
+        }
+        match String::from_utf8(line) {
+            Ok(line) => Ok(line),
+            Err(e) if self.lossy => {
+                self.lossy_lines += 1;
+                Ok(String::from_utf8_lossy(e.as_bytes()).into_owned())
+            }
+            Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RegionState {
+    Before,
+    Inside,
+    After,
+}
+
+/// Bounds the records to the region between a start and a stop marker, both excluded unless `inclusive`.
+/// The region starts with the first record without a start marker and is unbounded without a stop marker.
+/// With `repeat`, every following region is passed as well.
+pub(crate) struct Region {
+    start: Option<Regex>,
+    stop: Option<Regex>,
+    state: RegionState,
+    inclusive: bool,
+    repeat: bool,
+}
+
+impl Default for Region {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl Region {
+    pub fn new(start: Option<Regex>, stop: Option<Regex>) -> Self {
+        let state = match start {
+            Some(_) => RegionState::Before,
+            None => RegionState::Inside,
+        };
+        Self {
+            start,
+            stop,
+            state,
+            inclusive: false,
+            repeat: false,
+        }
+    }
+
+    pub fn between(start: Regex, stop: Regex, options: BetweenOptions) -> Self {
+        Self {
+            inclusive: options.inclusive,
+            repeat: options.repeat,
+            ..Self::new(Some(start), Some(stop))
+        }
+    }
+
+    /// Returns whether `record` is before, inside or after the region.
+    pub fn check(&mut self, record: &str) -> RegionState {
+        let matches = |rgx: &Option<Regex>| {
+            rgx.as_ref()
+                .is_some_and(|rgx| matches!(rgx.is_match(record), Ok(true)))
+        };
+        let after = if self.repeat {
+            RegionState::Before
+        } else {
+            RegionState::After
+        };
+        match self.state {
+            RegionState::Before if matches(&self.start) => {
+                if !self.inclusive {
+                    self.state = RegionState::Inside;
+                    return RegionState::Before;
                 }
-                if is_ignored(&line, &self.excludes) {
-                    continue;
+                self.state = if matches(&self.stop) {
+                    after
+                } else {
+                    RegionState::Inside
+                };
+                RegionState::Inside
+            }
+            RegionState::Inside if matches(&self.stop) => {
+                self.state = after;
+                if self.inclusive {
+                    RegionState::Inside
+                } else {
+                    after
                 }
-                return Some(Ok(line));
-            } else {
-                return next_line;
             }
+            state => state,
         }
     }
 }
 
-fn is_ignored(line: &str, ignores: &Vec<Regex>) -> bool {
-    for rgx in ignores {
-        if let Ok(Some(_)) = rgx.captures(line) {
-            return true;
+/// Keeps every `stride`th of the records passed to `keep`, starting with the first one.
+pub(crate) struct Stride {
+    stride: usize,
+    skip: usize,
+}
+
+impl Default for Stride {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Stride {
+    pub fn new(stride: usize) -> Self {
+        Self {
+            stride: stride.max(1),
+            skip: 0,
         }
     }
-    false
+
+    pub fn keep(&mut self) -> bool {
+        if self.skip > 0 {
+            self.skip -= 1;
+            return false;
+        }
+        self.skip = self.stride - 1;
+        true
+    }
+}
+
+/// Detects records equal to the previous one, ignoring the parts matching `ignore`.
+pub(crate) struct Dedup {
+    ignore: Option<Regex>,
+    last: Option<String>,
 }
 
-fn is_included(line: &str, includes: &Vec<Regex>) -> bool {
-    for rgx in includes {
-        if let Ok(Some(_)) = rgx.captures(line) {
+impl Dedup {
+    pub fn new(ignore: Option<Regex>) -> Self {
+        Self { ignore, last: None }
+    }
+
+    pub fn is_repeat(&mut self, record: &str) -> bool {
+        let key = match &self.ignore {
+            Some(ignore) => ignore.replace_all(record, ""),
+            None => Cow::Borrowed(record),
+        };
+        if self.last.as_deref() == Some(&key) {
             return true;
         }
+        self.last = Some(key.into_owned());
+        false
+    }
+}
+
+/// Joins consecutive lines into multiline records.
+///
+/// A record starts with a line matching `start` and contains all following lines up to the next
+/// start line. Lines preceding the first start line form a record of their own.
+pub(crate) struct RecordJoiner {
+    start: Regex,
+    record: Option<(usize, String)>,
+}
+
+impl RecordJoiner {
+    pub fn new(start: Regex) -> Self {
+        Self {
+            start,
+            record: None,
+        }
+    }
+
+    /// Adds the line with the given number, returning the previous record and the number
+    /// of its first line if `line` starts a new one.
+    pub fn push(&mut self, line: String, number: usize) -> Option<(usize, String)> {
+        if matches!(self.start.is_match(&line), Ok(true)) {
+            return self.record.replace((number, line));
+        }
+        match &mut self.record {
+            Some((_, record)) => {
+                record.push('\n');
+                record.push_str(&line);
+            }
+            None => self.record = Some((number, line)),
+        }
+        None
+    }
+
+    /// Returns the last record and the number of its first line once the input is exhausted.
+    pub fn finish(&mut self) -> Option<(usize, String)> {
+        self.record.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "a 1\nb 2\nERROR 3\nc 4\nd 5\ne 6\nERROR 7\nf 8\n";
+
+    fn lines(iter: FilterIter<&[u8]>) -> Vec<String> {
+        iter.collect::<io::Result<_>>().unwrap()
+    }
+
+    fn errors() -> FilterIter<&'static [u8]> {
+        FilterIter::new(LOG.as_bytes(), vec![Regex::new("ERROR").unwrap()], vec![])
+    }
+
+    #[test]
+    fn context_lines_are_returned_once() {
+        assert_eq!(
+            lines(errors().with_before_context(1)),
+            ["b 2", "ERROR 3", "e 6", "ERROR 7"]
+        );
+        assert_eq!(
+            lines(errors().with_after_context(1)),
+            ["ERROR 3", "c 4", "ERROR 7", "f 8"]
+        );
+        // The context of both matches overlaps at "d 5", which is returned only once.
+        assert_eq!(
+            lines(errors().with_before_context(2).with_after_context(2)),
+            ["a 1", "b 2", "ERROR 3", "c 4", "d 5", "e 6", "ERROR 7", "f 8"]
+        );
+    }
+
+    #[test]
+    fn invert_keeps_the_excludes() {
+        let iter = FilterIter::new(
+            LOG.as_bytes(),
+            vec![Regex::new("ERROR").unwrap()],
+            vec![Regex::new("^[a-c] ").unwrap()],
+        )
+        .with_invert(true);
+        assert_eq!(lines(iter), ["d 5", "e 6", "f 8"]);
+    }
+
+    #[test]
+    fn filter_between_passes_the_regions() {
+        let between = |options| {
+            lines(
+                FilterIter::new(LOG.as_bytes(), vec![], vec![]).filter_between(
+                    Regex::new("ERROR").unwrap(),
+                    Regex::new("^d|^f").unwrap(),
+                    options,
+                ),
+            )
+        };
+        assert_eq!(between(BetweenOptions::default()), ["c 4"]);
+        assert_eq!(
+            between(BetweenOptions {
+                inclusive: true,
+                repeat: true
+            }),
+            ["ERROR 3", "c 4", "d 5", "ERROR 7", "f 8"]
+        );
+    }
+
+    #[test]
+    fn dedup_ignores_the_given_parts() {
+        let input = "10:00 up\n10:01 up\n10:02 down\n10:03 up\n";
+        let iter = FilterIter::new(input.as_bytes(), vec![], vec![])
+            .with_dedup_consecutive(Some(Regex::new(r"^\d+:\d+ ").unwrap()));
+        assert_eq!(lines(iter), ["10:00 up", "10:02 down", "10:03 up"]);
+    }
+
+    #[test]
+    fn unreadable_lines_are_errors() {
+        let mut iter = FilterIter::new(&b"ok\n\xff\nok again\n"[..], vec![], vec![]);
+        assert_eq!(iter.next().unwrap().unwrap(), "ok");
+        assert!(iter.next().unwrap().is_err());
+        assert_eq!(iter.line_number(), 2);
+        assert_eq!(iter.next().unwrap().unwrap(), "ok again");
+    }
+
+    #[test]
+    fn positions_locate_the_matches() {
+        let mut iter = FilterIter::new(
+            LOG.as_bytes(),
+            vec![Regex::new("^f").unwrap(), Regex::new(r"\d$").unwrap()],
+            vec![],
+        )
+        .with_before_context(1)
+        .with_max_lines(3)
+        .with_positions();
+        let line = iter.next().unwrap().unwrap();
+        assert_eq!((line.line_number, line.text.as_str()), (1, "a 1"));
+        assert_eq!((line.pattern_index, line.span), (Some(1), Some(2..3)));
+        iter.next();
+        iter.next();
+        assert!(iter.next().is_none());
+
+        let lines = crate::filter_lines(LOG.as_bytes(), vec![Regex::new("ERROR").unwrap()], vec![])
+            .unwrap();
+        let numbers: Vec<_> = lines.iter().map(|l| l.line_number).collect();
+        assert_eq!(numbers, [3, 7]);
+        assert_eq!(lines[0].span, Some(0..5));
+    }
+
+    #[test]
+    fn head_and_tail_limit_the_records() {
+        let all = || FilterIter::new(LOG.as_bytes(), vec![], vec![]);
+        assert_eq!(lines(all().with_max_lines(2)), ["a 1", "b 2"]);
+        assert_eq!(lines(all().with_last_lines(2)), ["ERROR 7", "f 8"]);
+        assert_eq!(lines(all().with_last_lines(3).with_max_lines(1)), ["e 6"]);
+        assert_eq!(lines(errors().with_last_lines(5)), ["ERROR 3", "ERROR 7"]);
+
+        let mut head = all().with_max_lines(1);
+        head.by_ref().for_each(drop);
+        assert_eq!(head.lines_read(), 1);
+    }
+
+    #[test]
+    fn replacements_are_applied_in_order() {
+        let iter = errors()
+            .with_replacement(Regex::new(r"(?P<word>[A-Z]+) (\d)").unwrap(), "$2 ${word}")
+            .with_replacement(Regex::new("ERROR").unwrap(), "E");
+        assert_eq!(lines(iter), ["3 E", "7 E"]);
+
+        let mut positions = errors()
+            .with_replacement(Regex::new("^").unwrap(), "> ")
+            .with_positions();
+        assert_eq!(positions.next().unwrap().unwrap().span, Some(2..7));
+    }
+
+    #[test]
+    fn only_matching_returns_each_match() {
+        let digits = || {
+            FilterIter::new(
+                "a 1 2\nb\nc 3\n".as_bytes(),
+                vec![Regex::new(r"(\d)").unwrap()],
+                vec![],
+            )
+        };
+        assert_eq!(lines(digits().with_only_matching(1)), ["1", "2", "3"]);
+        assert_eq!(
+            lines(digits().with_only_matching(0).with_after_context(1)),
+            ["1", "2", "b", "3"]
+        );
+        assert!(lines(digits().with_only_matching(2)).is_empty());
+    }
+
+    #[test]
+    fn records_are_split_at_the_delimiter() {
+        let input = "a 1\0ERROR 2\nb\0ERROR 3\0";
+        let split = |delimiter: &str| {
+            lines(FilterIter::new(input.as_bytes(), vec![], vec![]).with_delimiter(delimiter))
+        };
+        assert_eq!(split("\0"), ["a 1", "ERROR 2\nb", "ERROR 3"]);
+        assert_eq!(split(""), ["a 1\0ERROR 2", "b\0ERROR 3\0"]);
+        assert_eq!(split("ERROR "), ["a 1\0", "2\nb\0", "3\0"]);
     }
-    includes.is_empty()
 }
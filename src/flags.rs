@@ -0,0 +1,57 @@
+use fancy_regex::Regex;
+
+/// Flags applied to expressions when compiling them, instead of writing `(?i)` or `(?s)` into every pattern.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegexFlags {
+    /// Letters match both upper and lower case, like `(?i)`.
+    pub case_insensitive: bool,
+    /// `.` also matches line breaks, e.g. within multiline records, like `(?s)`.
+    pub dot_matches_newline: bool,
+}
+
+impl RegexFlags {
+    /// Returns `pattern` with the flags prepended as inline flags.
+    pub fn apply(&self, pattern: &str) -> String {
+        let flags: String = [
+            (self.case_insensitive, 'i'),
+            (self.dot_matches_newline, 's'),
+        ]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect();
+        if flags.is_empty() {
+            pattern.to_string()
+        } else {
+            format!("(?{}){}", flags, pattern)
+        }
+    }
+
+    /// Compiles `pattern` with the flags, e.g. for include and exclude expressions.
+    pub fn compile(&self, pattern: &str) -> Result<Regex, fancy_regex::Error> {
+        Regex::new(&self.apply(pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_are_prepended_as_inline_flags() {
+        let flags = RegexFlags {
+            case_insensitive: true,
+            dot_matches_newline: true,
+        };
+        assert_eq!(flags.apply("temp=(.+)"), "(?is)temp=(.+)");
+        assert_eq!(RegexFlags::default().apply("temp"), "temp");
+
+        let regex = flags.compile("error.+done").unwrap();
+        assert!(regex.is_match("ERROR\ndone").unwrap());
+        assert!(!RegexFlags::default()
+            .compile("error.+done")
+            .unwrap()
+            .is_match("ERROR\ndone")
+            .unwrap());
+    }
+}
@@ -0,0 +1,59 @@
+use std::{
+    io::Read,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Wraps a reader which is still being written to, like a log file.
+///
+/// Instead of signalling the end of the input, reading waits for new data to arrive (like `tail -f`),
+/// polling the inner reader every `poll_interval`. Truncated or rotated files are not detected.
+/// The input only ends once the cancel token is set, see [`FollowReader::with_cancel_token`].
+#[derive(Debug)]
+pub struct FollowReader<R> {
+    inner: R,
+    poll_interval: Duration,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl<R> FollowReader<R> {
+    pub fn new(inner: R, poll_interval: Duration) -> Self {
+        Self {
+            inner,
+            poll_interval,
+            cancel: None,
+        }
+    }
+
+    /// Ends the input once `token` is set, which is checked while waiting for new data,
+    /// so following an idle file stops within one `poll_interval`.
+    pub fn with_cancel_token(mut self, token: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for FollowReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let n = self.inner.read(buf)?;
+            let cancelled = || {
+                self.cancel
+                    .as_ref()
+                    .is_some_and(|c| c.load(Ordering::Relaxed))
+            };
+            if n > 0 || buf.is_empty() || cancelled() {
+                return Ok(n);
+            }
+            thread::sleep(self.poll_interval);
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use std::{borrow::Cow, fmt, sync::Arc};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::numeric::Numeric;
+
+/// Defines how the matched text is converted into a value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ValueFormat {
+    /// A plain number like `12.5` or `-3e2`, or an integer literal with `0x`, `0o` or `0b` prefix like `0x1A2B`.
+    #[default]
+    Number,
+    /// An integer in the given radix like `1A2B` for radix 16. A matching `0x`, `0o` or `0b` prefix is allowed.
+    Radix(u32),
+    /// A timestamp parsed with a chrono/strftime format like `%Y-%m-%d %H:%M:%S%.f`,
+    /// converted into seconds (truncated for integer types). Timestamps without date are seconds since midnight,
+    /// timestamps without timezone are interpreted as UTC.
+    Timestamp { format: String, origin: TimeOrigin },
+    /// A number with an optional unit suffix like `12ms`, `3.4k` or `1.2 MiB`, converted into the given unit,
+    /// e.g. `s`, `B` or an empty string for plain numbers. SI and binary prefixes as well as the time units
+    /// `min`, `h` and `d` are recognized. Numbers without suffix are already in the given unit,
+    /// numbers with an incompatible unit are missing.
+    Unit(String),
+    /// A boolean like `true`/`false`, `yes`/`no` or `on`/`off` (case-insensitive), converted into 1 or 0.
+    Bool,
+    /// The matched text itself, stored in a text column.
+    Text,
+}
+
+/// The point in time timestamps are measured from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeOrigin {
+    /// Seconds since the unix epoch.
+    #[default]
+    Epoch,
+    /// Seconds since the first timestamp of the column.
+    ///
+    /// The offset is computed after converting into the value type, so `f64` should be used
+    /// to keep sub-second precision.
+    FirstTimestamp,
+}
+
+/// The separators used by plain numbers and numbers with unit, e.g. `1.234,56` in many European locales.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumberFormat {
+    pub decimal_separator: char,
+    pub thousands_separator: Option<char>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            thousands_separator: None,
+        }
+    }
+}
+
+impl NumberFormat {
+    pub fn new(decimal_separator: char, thousands_separator: Option<char>) -> Self {
+        Self {
+            decimal_separator,
+            thousands_separator,
+        }
+    }
+
+    /// Removes the thousands separators and replaces the decimal separator by `.`.
+    fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if *self == Self::default() {
+            return Cow::Borrowed(text);
+        }
+        text.chars()
+            .filter(|c| Some(*c) != self.thousands_separator)
+            .map(|c| if c == self.decimal_separator { '.' } else { c })
+            .collect()
+    }
+}
+
+/// A conversion applied to each value right after parsing, e.g. from milliseconds to seconds.
+///
+/// The value is converted into `f64` and back, so integer results are truncated.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Transform {
+    /// Computes `value * scale + offset`.
+    Linear { scale: f64, offset: f64 },
+    /// Applies an arbitrary function. Cannot be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Arc<dyn Fn(f64) -> f64 + Send + Sync>),
+}
+
+impl Transform {
+    pub(crate) fn apply<T>(&self, value: T) -> Option<T>
+    where
+        T: Numeric,
+    {
+        let value = value.to_f64()?;
+        T::from(match self {
+            Transform::Linear { scale, offset } => value * scale + offset,
+            Transform::Custom(f) => f(value),
+        })
+    }
+}
+
+impl fmt::Debug for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transform::Linear { scale, offset } => f
+                .debug_struct("Linear")
+                .field("scale", scale)
+                .field("offset", offset)
+                .finish(),
+            Transform::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+pub(crate) fn parse_value<T>(
+    text: &str,
+    format: &ValueFormat,
+    number_format: Option<&NumberFormat>,
+) -> Option<T>
+where
+    T: Numeric,
+{
+    let number = |text| match number_format {
+        Some(number_format) => number_format.normalize(text),
+        None => Cow::Borrowed(text),
+    };
+    match format {
+        ValueFormat::Number => number(text)
+            .parse()
+            .ok()
+            .or_else(|| T::from(parse_integer(text, None)?)),
+        ValueFormat::Radix(radix) => T::from(parse_integer(text, Some(*radix))?),
+        ValueFormat::Timestamp { format, .. } => T::from(parse_timestamp(text, format)?),
+        ValueFormat::Unit(unit) => T::from(parse_unit(&number(text), unit)?),
+        ValueFormat::Bool => parse_bool(text).map(|b| if b { T::one() } else { T::zero() }),
+        ValueFormat::Text => None,
+    }
+}
+
+/// Parses an integer in `radix`, or in the radix given by its `0x`, `0o` or `0b` prefix.
+fn parse_integer(text: &str, radix: Option<u32>) -> Option<i128> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let prefixed = [
+        ("0x", 16),
+        ("0X", 16),
+        ("0o", 8),
+        ("0O", 8),
+        ("0b", 2),
+        ("0B", 2),
+    ]
+    .into_iter()
+    .filter(|(_, r)| radix.is_none_or(|radix| radix == *r))
+    .find_map(|(prefix, r)| Some((r, digits.strip_prefix(prefix)?)));
+    let (radix, digits) = match (prefixed, radix) {
+        (Some(prefixed), _) => prefixed,
+        (None, Some(radix)) => (radix, digits),
+        (None, None) => return None,
+    };
+    if !(2..=36).contains(&radix) || digits.starts_with(['+', '-']) {
+        return None;
+    }
+    let value = i128::try_from(u128::from_str_radix(digits, radix).ok()?).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+const SI_PREFIXES: [(&str, f64); 11] = [
+    ("p", 1e-12),
+    ("n", 1e-9),
+    ("u", 1e-6),
+    ("µ", 1e-6),
+    ("m", 1e-3),
+    ("k", 1e3),
+    ("K", 1e3),
+    ("M", 1e6),
+    ("G", 1e9),
+    ("T", 1e12),
+    ("P", 1e15),
+];
+
+const BINARY_PREFIXES: [(&str, f64); 5] = [
+    ("Ki", 1024.0),
+    ("Mi", 1048576.0),
+    ("Gi", 1073741824.0),
+    ("Ti", 1099511627776.0),
+    ("Pi", 1125899906842624.0),
+];
+
+const TIME_UNITS: [(&str, f64); 3] = [("min", 60.0), ("h", 3600.0), ("d", 86400.0)];
+
+/// Parses a number with an optional unit suffix and converts it into `target`.
+fn parse_unit(text: &str, target: &str) -> Option<f64> {
+    let text = text.trim();
+    let (value, suffix) = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([text.len()])
+        .rev()
+        .find_map(|i| Some((text[..i].trim_end().parse::<f64>().ok()?, text[i..].trim())))?;
+    if suffix.is_empty() {
+        return Some(value);
+    }
+
+    let targets = unit_factors(target);
+    unit_factors(suffix).into_iter().find_map(|(factor, base)| {
+        targets
+            .iter()
+            .find(|(_, b)| *b == base)
+            .map(|(target_factor, _)| value * factor / target_factor)
+    })
+}
+
+/// Returns all interpretations of `unit` as factor of a base unit, the unit itself first.
+fn unit_factors(unit: &str) -> Vec<(f64, &str)> {
+    let mut factors = vec![(1.0, unit)];
+    factors.extend(
+        TIME_UNITS
+            .iter()
+            .filter(|(u, _)| *u == unit)
+            .map(|(_, factor)| (*factor, "s")),
+    );
+    factors.extend(
+        BINARY_PREFIXES
+            .iter()
+            .chain(&SI_PREFIXES)
+            .filter_map(|(prefix, factor)| Some((*factor, unit.strip_prefix(prefix)?))),
+    );
+    factors
+}
+
+/// Parses a timestamp into seconds since the unix epoch, or since midnight for times without date.
+fn parse_timestamp(text: &str, format: &str) -> Option<f64> {
+    let micros = if let Ok(t) = DateTime::parse_from_str(text, format) {
+        t.timestamp_micros()
+    } else if let Ok(t) = NaiveDateTime::parse_from_str(text, format) {
+        t.and_utc().timestamp_micros()
+    } else if let Ok(t) = NaiveDate::parse_from_str(text, format) {
+        t.and_time(NaiveTime::MIN).and_utc().timestamp_micros()
+    } else {
+        let t = NaiveTime::parse_from_str(text, format).ok()?;
+        (t - NaiveTime::MIN).num_microseconds()?
+    };
+    Some(micros as f64 / 1e6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_unit_converts_prefixes_into_the_target_unit() {
+        assert_eq!(parse_unit("1.5 kB", "B"), Some(1500.0));
+        assert_eq!(parse_unit("2KiB", "B"), Some(2048.0));
+        assert_eq!(parse_unit("250ms", "s"), Some(0.25));
+        assert_eq!(parse_unit("2 min", "s"), Some(120.0));
+        assert_eq!(parse_unit("3MB", "kB"), Some(3000.0));
+        assert_eq!(parse_unit("42", "ms"), Some(42.0));
+        assert_eq!(parse_unit("5 V", "A"), None);
+    }
+
+    #[test]
+    fn parse_value_handles_formats() {
+        let unit = ValueFormat::Unit("s".into());
+        assert_eq!(parse_value::<f64>("500 ms", &unit, None), Some(0.5));
+        assert_eq!(
+            parse_value::<i64>("0x1F", &ValueFormat::Number, None),
+            Some(31)
+        );
+        assert_eq!(
+            parse_value::<f64>("ff", &ValueFormat::Radix(16), None),
+            Some(255.0)
+        );
+        assert_eq!(
+            parse_value::<f64>("Off", &ValueFormat::Bool, None),
+            Some(0.0)
+        );
+        assert_eq!(parse_value::<f64>("n/a", &ValueFormat::Number, None), None);
+    }
+
+    #[test]
+    fn number_formats_replace_the_separators() {
+        let german = NumberFormat::new(',', Some('.'));
+        let parse = |text| parse_value::<f64>(text, &ValueFormat::Number, Some(&german));
+        assert_eq!(parse("1.234,5"), Some(1234.5));
+        assert_eq!(parse("-0,25"), Some(-0.25));
+        assert_eq!(
+            parse_value::<f64>("1,5 kB", &ValueFormat::Unit("B".into()), Some(&german)),
+            Some(1500.0)
+        );
+        assert_eq!(parse_value::<f64>("1,5", &ValueFormat::Number, None), None);
+    }
+}
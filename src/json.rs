@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use serde_json::value::RawValue;
+
+/// Returns the value at `path` in the JSON object `line`, e.g. `["status", "temp"]` for `status.temp`.
+///
+/// Array elements are selected by their index. Strings are returned without quotes and escape sequences
+/// are kept as is. Returns `None` if `line` is not valid JSON, the path does not exist or the value is `null`.
+pub(crate) fn select<'t>(line: &'t str, path: &[String]) -> Option<&'t str> {
+    let mut value: &'t RawValue = serde_json::from_str(line).ok()?;
+    for key in path {
+        let raw = value.get();
+        value = if raw.starts_with('{') {
+            let object: HashMap<String, &'t RawValue> = serde_json::from_str(raw).ok()?;
+            object.get(key).copied()?
+        } else if raw.starts_with('[') {
+            let array: Vec<&'t RawValue> = serde_json::from_str(raw).ok()?;
+            array.get(key.parse::<usize>().ok()?).copied()?
+        } else {
+            return None;
+        };
+    }
+
+    match value.get() {
+        "null" => None,
+        raw => Some(
+            raw.strip_prefix('"')
+                .and_then(|r| r.strip_suffix('"'))
+                .unwrap_or(raw),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: &str) -> Vec<String> {
+        path.split('.').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn values_are_selected_by_dotted_paths() {
+        let line = r#"{"status": {"temp": 20.5, "values": [1, "two"], "unit": "C", "none": null}}"#;
+        assert_eq!(select(line, &path("status.temp")), Some("20.5"));
+        assert_eq!(select(line, &path("status.values.1")), Some("two"));
+        assert_eq!(select(line, &path("status.unit")), Some("C"));
+        assert_eq!(select(line, &path("status.none")), None);
+        assert_eq!(select(line, &path("status.temp.value")), None);
+        assert_eq!(select(line, &path("status.values.x")), None);
+        assert_eq!(select("temp=20", &path("temp")), None);
+    }
+}
@@ -1,27 +1,386 @@
-use datatable::{datatable_error::DataTableError, DataTable};
-use error::ExtractionError;
-use fancy_regex::Regex;
-use std::io::Read;
+use datatable::{
+    aggregation::Aggregation, datatable_error::DataTableError, metadata::ColumnMeta, DataTable,
+};
+use diagnostics::LineIssues;
+use error::{ExtractionError, RegexBuildError};
+use extractor::Extractor;
+use fancy_regex::{Captures, Regex};
+use flags::RegexFlags;
+use format::{parse_value, NumberFormat, TimeOrigin, Transform, ValueFormat};
+use numeric::Numeric;
+use prefilter::Prefilter;
+use std::{io::Read, sync::Arc};
 
+#[cfg(feature = "async")]
+pub mod async_filter_iter;
+pub mod bytes;
+pub mod chain;
+#[cfg(feature = "serde")]
+pub mod config;
 pub mod datatable;
+pub mod decompress;
+pub mod diagnostics;
+#[cfg(feature = "encoding")]
+pub mod encoding;
 pub mod error;
-mod filter_iter;
-#[derive(Debug)]
+mod extract_iter;
+pub mod extractor;
+pub mod filter_iter;
+pub mod flags;
+pub mod follow;
+pub mod format;
+#[cfg(feature = "json")]
+mod json;
+pub mod logfmt;
+pub mod numeric;
+mod prefilter;
+#[cfg(feature = "serde")]
+mod serde_regex;
+
+/// Selects which part of a match is used as the extracted value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Capture {
+    /// The full match, or the first group if extraction runs with `group` set.
+    #[default]
+    Positional,
+    /// The capture group with the given name, e.g. `temp` for `(?P<temp>\d+)`.
+    Named(String),
+    /// Every named capture group, each one populating a column named after the group.
+    AllNamed,
+    /// The values of `key=value` pairs with the given keys, each one populating a column named after the key.
+    Logfmt(Vec<String>),
+    /// The value at a path of keys in JSON lines, e.g. `["status", "temp"]`.
+    #[cfg(feature = "json")]
+    JsonPath(Vec<String>),
+}
+
+/// Defines how several matches of a regex within one line are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Repeat {
+    /// Only the first match is used.
+    #[default]
+    First,
+    /// Every match produces its own row. Columns of regexes which are not repeated
+    /// keep their value for all rows of the line, repeated columns with fewer matches
+    /// are filled with NaN.
+    NewRow,
+    /// All matches are combined into a single value.
+    Aggregate(Aggregation),
+}
+
+/// A regex extracting the values of one or more columns.
+///
+/// With the `serde` feature the regex is (de)serialized as its pattern and all fields except
+/// `name` and `regex` are optional, so extraction configs can be loaded from files.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NamedRegex {
     pub name: String,
+    #[cfg_attr(feature = "serde", serde(with = "serde_regex"))]
     pub regex: Regex,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub capture: Capture,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub repeat: Repeat,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub format: ValueFormat,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub number_format: Option<NumberFormat>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub transform: Option<Transform>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub required: bool,
 }
 
 impl NamedRegex {
+    /// Returns `None` if the expression is invalid, see [`NamedRegex::try_new`] for the reason.
     pub fn new_from_string(name: &str, regex: &str) -> Option<Self> {
+        Self::try_new(name, regex).ok()
+    }
+
+    /// Creates a regex whose value is the full match, or the first group in group mode.
+    ///
+    /// Returns the compilation error if the expression is invalid.
+    pub fn try_new(name: &str, regex: &str) -> Result<Self, RegexBuildError> {
+        Ok(Self {
+            name: name.into(),
+            regex: Regex::new(regex).map_err(|source| RegexBuildError {
+                name: name.into(),
+                pattern: regex.into(),
+                source,
+            })?,
+            capture: Capture::Positional,
+            repeat: Repeat::First,
+            format: ValueFormat::Number,
+            number_format: None,
+            transform: None,
+            required: false,
+        })
+    }
+
+    /// Recompiles the expression with the given flags, e.g. to match case-insensitively.
+    ///
+    /// Returns the compilation error if the flags make the expression invalid.
+    pub fn with_flags(mut self, flags: RegexFlags) -> Result<Self, RegexBuildError> {
+        let pattern = flags.apply(self.regex.as_str());
+        self.regex = Regex::new(&pattern).map_err(|source| RegexBuildError {
+            name: self.name.clone(),
+            pattern,
+            source,
+        })?;
+        Ok(self)
+    }
+
+    /// Creates a regex whose value is taken from a named capture group.
+    ///
+    /// If `capture_name` is `None` the first named group of the expression is used.
+    /// If `name` is `None` the column is named after the capture group.
+    /// Returns `None` if the expression is invalid or does not contain the group.
+    pub fn new_with_capture(
+        name: Option<&str>,
+        regex: &str,
+        capture_name: Option<&str>,
+    ) -> Option<Self> {
+        let regex = Regex::new(regex).ok()?;
+        let capture_name = match capture_name {
+            Some(c) => regex.capture_names().flatten().find(|n| *n == c)?,
+            None => regex.capture_names().flatten().next()?,
+        }
+        .to_string();
+        Some(Self {
+            name: name
+                .map(str::to_string)
+                .unwrap_or_else(|| capture_name.clone()),
+            regex,
+            capture: Capture::Named(capture_name),
+            repeat: Repeat::First,
+            format: ValueFormat::Number,
+            number_format: None,
+            transform: None,
+            required: false,
+        })
+    }
+
+    /// Creates a regex populating one column per named capture group,
+    /// e.g. `X:(?P<x>\S+) Y:(?P<y>\S+)` yields the columns `x` and `y`.
+    ///
+    /// Returns `None` if the expression is invalid or does not contain any named group.
+    pub fn new_multi_capture(name: &str, regex: &str) -> Option<Self> {
+        let regex = Regex::new(regex).ok()?;
+        regex.capture_names().flatten().next()?;
         Some(Self {
             name: name.into(),
-            regex: Regex::new(regex).ok()?,
+            regex,
+            capture: Capture::AllNamed,
+            repeat: Repeat::First,
+            format: ValueFormat::Number,
+            number_format: None,
+            transform: None,
+            required: false,
         })
     }
+
+    /// Creates a regex tokenizing logfmt lines like `temp=200.1 target=210 msg="heating up"`,
+    /// populating one column per key. Keys can be collected from a sample with `logfmt::keys`.
+    pub fn new_logfmt<S: AsRef<str>>(keys: &[S]) -> Self {
+        Self {
+            name: "logfmt".into(),
+            regex: Regex::new(logfmt::PAIR).expect("The pair expression is valid."),
+            capture: Capture::Logfmt(keys.iter().map(|k| k.as_ref().to_string()).collect()),
+            repeat: Repeat::First,
+            format: ValueFormat::Number,
+            number_format: None,
+            transform: None,
+            required: false,
+        }
+    }
+
+    /// Creates a regex selecting the value at a dotted `path` like `status.temp` in lines containing
+    /// a JSON object, e.g. JSON Lines logs. Array elements are selected by index like `values.0`.
+    /// Lines which are not JSON objects have no value.
+    #[cfg(feature = "json")]
+    pub fn new_json_path(name: &str, path: &str) -> Self {
+        Self {
+            name: name.into(),
+            regex: Regex::new(r"^\s*\{").expect("The object expression is valid."),
+            capture: Capture::JsonPath(path.split('.').map(str::to_string).collect()),
+            repeat: Repeat::First,
+            format: ValueFormat::Number,
+            number_format: None,
+            transform: None,
+            required: false,
+        }
+    }
+
+    /// Sets how several matches within one line are handled.
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Marks the regex as required.
+    ///
+    /// As soon as one regex is required, the values of consecutive lines are merged into one row,
+    /// which is emitted once every required column has a value since the last emitted row.
+    /// Optional columns without a value are handled by the missing value policy.
+    /// A final row lacking required values is dropped at the end of the input.
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Sets how the matched text is converted into a value.
+    pub fn with_format(mut self, format: ValueFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Parses the matched text as timestamp with the given chrono/strftime `format`,
+    /// e.g. `%Y-%m-%d %H:%M:%S%.f`.
+    pub fn with_timestamp(mut self, format: &str, origin: TimeOrigin) -> Self {
+        self.format = ValueFormat::Timestamp {
+            format: format.into(),
+            origin,
+        };
+        self
+    }
+
+    /// Parses the matched text as integer in the given `radix`, e.g. 16 for register dumps like `1A2B`.
+    pub fn with_radix(mut self, radix: u32) -> Self {
+        self.format = ValueFormat::Radix(radix);
+        self
+    }
+
+    /// Parses the matched text as number with an optional unit suffix like `12ms` or `1.2MiB`
+    /// and converts it into `unit`, see `ValueFormat::Unit`.
+    pub fn with_unit(mut self, unit: &str) -> Self {
+        self.format = ValueFormat::Unit(unit.into());
+        self
+    }
+
+    /// Sets the decimal and thousands separators of the matched numbers,
+    /// overriding the number format of the `Extractor`.
+    pub fn with_number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = Some(number_format);
+        self
+    }
+
+    /// Applies `transform` to every parsed value, replacing any previous transform.
+    pub fn with_transform(
+        mut self,
+        transform: impl Fn(f64) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        self.transform = Some(Transform::Custom(Arc::new(transform)));
+        self
+    }
+
+    /// Converts every parsed value into `value * scale + offset`, replacing any previous transform.
+    pub fn with_scale(mut self, scale: f64, offset: f64) -> Self {
+        self.transform = Some(Transform::Linear { scale, offset });
+        self
+    }
+
+    /// Extracts the matched text into a text column instead of a numeric one.
+    pub fn as_text(mut self) -> Self {
+        self.format = ValueFormat::Text;
+        self
+    }
+
+    /// Returns `true` if this regex populates text columns.
+    pub fn is_text(&self) -> bool {
+        self.format == ValueFormat::Text
+    }
+
+    /// Returns the names of the columns populated by this regex.
+    pub fn column_names(&self) -> Vec<String> {
+        match self.capture {
+            Capture::AllNamed => self
+                .regex
+                .capture_names()
+                .flatten()
+                .map(str::to_string)
+                .collect(),
+            Capture::Logfmt(ref keys) => keys.clone(),
+            _ => vec![self.name.clone()],
+        }
+    }
+
+    /// Returns the metadata of the columns populated by this regex.
+    pub(crate) fn column_meta(&self) -> ColumnMeta {
+        let source = match self.capture {
+            #[cfg(feature = "json")]
+            Capture::JsonPath(ref path) => path.join("."),
+            _ => self.regex.as_str().to_string(),
+        };
+        ColumnMeta {
+            unit: match self.format {
+                ValueFormat::Unit(ref unit) => Some(unit.clone()),
+                _ => None,
+            },
+            description: None,
+            source: Some(source),
+        }
+    }
+
+    fn column_count(&self) -> usize {
+        match self.capture {
+            Capture::AllNamed => self.regex.capture_names().flatten().count(),
+            Capture::Logfmt(ref keys) => keys.len(),
+            _ => 1,
+        }
+    }
+}
+/// A single row of extracted values, one per column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row<T> {
+    names: Arc<[String]>,
+    values: Vec<T>,
+    text_names: Arc<[String]>,
+    texts: Vec<String>,
+}
+
+impl<T: Copy> Row<T> {
+    /// Returns the numeric column names, in the same order as the values.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the value of the column with the given name.
+    pub fn get(&self, name: &str) -> Option<T> {
+        let index = self.names.iter().position(|n| n == name)?;
+        self.values.get(index).copied()
+    }
+
+    pub fn into_values(self) -> Vec<T> {
+        self.values
+    }
+
+    /// Returns the text column names, in the same order as the texts.
+    pub fn text_names(&self) -> &[String] {
+        &self.text_names
+    }
+
+    pub fn texts(&self) -> &[String] {
+        &self.texts
+    }
+
+    /// Returns the text of the text column with the given name.
+    pub fn get_text(&self, name: &str) -> Option<&str> {
+        let index = self.text_names.iter().position(|n| n == name)?;
+        self.texts.get(index).map(String::as_str)
+    }
 }
+
 /// Extracts and processes data from an input source based on regular expressions.
 ///
+/// This is a shorthand for configuring and running an [`Extractor`].
+///
 /// This function takes an input data source implementing the `Read` trait and extracts specific data
 /// patterns based on provided regular expressions (`data_regex`). It filters lines from the input
 /// based on inclusion and exclusion criteria specified by `included_lines` and `excluded_lines`.
@@ -34,10 +393,12 @@ impl NamedRegex {
 ///
 /// - `reader`: An input source implementing the `Read` trait.
 /// - `data_regex`: A vector of named regular expressions (`NamedRegex`) for data extraction.
+///   A regex matching several times within a line produces several rows if its `repeat` is `Repeat::NewRow`.
 /// - `included_lines`: A vector of regular expressions (`Regex`) for line inclusion criteria.
 /// - `excluded_lines`: A vector of regular expressions (`Regex`) for line exclusion criteria.
 /// - `base_data_name`: An optional string reference (`Option<&str>`) for setting a base name for extracted data.
-/// - `group`: A boolean flag indicating whether the data are contained in the first group of each regular expression
+/// - `group`: A boolean flag indicating whether the data are contained in the first group of each regular expression.
+///   Regular expressions with a named capture (`Capture::Named`) always use that group.
 ///
 /// # Type Parameters
 ///
@@ -62,29 +423,152 @@ pub fn extract_data<Reader, T>(
 ) -> Result<DataTable<T>, ExtractionError>
 where
     Reader: Read,
-    T: Copy + num::Float + std::str::FromStr,
+    T: Numeric,
 {
-    let mut builder: datatable::builder::DataTableBuilder<T> =
-        datatable::builder::DataTableBuilder::new(
-            &data_regex
-                .iter()
-                .map(|r| r.name.clone())
-                .collect::<Vec<_>>(),
-        )?;
+    Extractor {
+        data_regex,
+        included_lines,
+        excluded_lines,
+        base_data_name: base_data_name.map(str::to_string),
+        group,
+        ..Extractor::default()
+    }
+    .run(reader)
+}
 
-    filter_iter::FilterIter::new(reader, included_lines, excluded_lines).try_for_each(
-        |filtered_line| -> Result<(), DataTableError> {
-            if let Ok(line) = filtered_line {
-                for (name, value) in get_numbers(&line, &data_regex, group) {
-                    builder.add_value(&name, value)?;
-                }
-            }
-            Ok(())
-        },
-    )?;
+/// Extracts and processes data from several input sources into a single data table.
+///
+/// This works like [`extract_data`], processing the inputs one after another, e.g. rotated log files.
+/// The name of the input each row came from is stored in the additional text column `source`.
+///
+/// # Parameters
+///
+/// - `readers`: A vector of input names and input sources implementing the `Read` trait.
+/// - `data_regex`: A vector of named regular expressions (`NamedRegex`) for data extraction.
+/// - `included_lines`: A vector of regular expressions (`Regex`) for line inclusion criteria.
+/// - `excluded_lines`: A vector of regular expressions (`Regex`) for line exclusion criteria.
+/// - `base_data_name`: An optional string reference (`Option<&str>`) for setting a base name for extracted data.
+/// - `group`: A boolean flag indicating whether the data are contained in the first group of each regular expression.
+///
+/// # Type Parameters
+///
+/// - `Reader`: A type implementing the `Read` trait for the input data sources.
+/// - `T`: A generic type parameter that represents the data type of values in the resulting data table.
+///
+/// # Returns
+///
+/// - `Result<DataTable<T>, ExtractionError>`: A `Result` containing the constructed data table
+///   or an `ExtractionError` in case of an error during the extraction and processing.
+///
+/// # Errors
+///
+/// This function may return an `ExtractionError` in case of errors during data extraction or table construction,
+/// e.g. if a text column is already named `source`.
+pub fn extract_data_multi<Reader, T>(
+    readers: Vec<(String, Reader)>,
+    data_regex: Vec<NamedRegex>,
+    included_lines: Vec<Regex>,
+    excluded_lines: Vec<Regex>,
+    base_data_name: Option<&str>,
+    group: bool,
+) -> Result<DataTable<T>, ExtractionError>
+where
+    Reader: Read,
+    T: Numeric,
+{
+    Extractor {
+        data_regex,
+        included_lines,
+        excluded_lines,
+        base_data_name: base_data_name.map(str::to_string),
+        group,
+        ..Extractor::default()
+    }
+    .run_multi(readers, "source")
+}
 
-    let dt = builder.build(base_data_name)?;
-    Ok(dt)
+/// Lazily extracts rows of data from an input source based on regular expressions.
+///
+/// This is the streaming counterpart of [`extract_data`]: instead of collecting all values into a
+/// `DataTable`, the rows are yielded while the input is consumed, so arbitrarily large inputs can be
+/// processed in constant memory. Every filtered line yields at least one row; values which could not
+/// be extracted are NaN. For types without NaN (integers) rows with missing values are skipped,
+/// use an [`Extractor`] to configure a different [`extractor::MissingPolicy`].
+///
+/// # Parameters
+///
+/// - `reader`: An input source implementing the `Read` trait.
+/// - `data_regex`: A vector of named regular expressions (`NamedRegex`) for data extraction.
+/// - `included_lines`: A vector of regular expressions (`Regex`) for line inclusion criteria.
+/// - `excluded_lines`: A vector of regular expressions (`Regex`) for line exclusion criteria.
+/// - `group`: A boolean flag indicating whether the data are contained in the first group of each regular expression.
+///
+/// # Type Parameters
+///
+/// - `Reader`: A type implementing the `Read` trait for the input data source.
+/// - `T`: A generic type parameter that represents the data type of the extracted values.
+///
+/// # Returns
+///
+/// - `impl Iterator<Item = Result<Row<T>, ExtractionError>>`: An iterator over the extracted rows.
+///
+/// # Errors
+///
+/// The iterator yields an `ExtractionError::ReadError` for lines which could not be read.
+pub fn extract_iter<Reader, T>(
+    reader: Reader,
+    data_regex: Vec<NamedRegex>,
+    included_lines: Vec<Regex>,
+    excluded_lines: Vec<Regex>,
+    group: bool,
+) -> impl Iterator<Item = Result<Row<T>, ExtractionError>>
+where
+    Reader: Read,
+    T: Numeric,
+{
+    let extractor = Extractor {
+        data_regex,
+        included_lines,
+        excluded_lines,
+        group,
+        ..Extractor::default()
+    };
+    extract_iter::ExtractIter::new(reader, extractor)
+}
+
+/// Lazily filters lines from an input source based on regular expressions.
+///
+/// This is the streaming counterpart of [`filter`]: the lines passing the filters are yielded while
+/// the input is consumed, so arbitrarily large inputs can be processed in constant memory.
+/// The returned [`FilterIter`](filter_iter::FilterIter) offers further options like skipping
+/// header lines or joining multiline records.
+///
+/// # Parameters
+///
+/// - `reader`: An input source implementing the `Read` trait.
+/// - `included_lines`: A vector of regular expressions (`Regex`) for line inclusion criteria.
+/// - `excluded_lines`: A vector of regular expressions (`Regex`) for line exclusion criteria.
+///
+/// # Type Parameters
+///
+/// - `Reader`: A type implementing the `Read` trait for the input data source.
+///
+/// # Returns
+///
+/// - `FilterIter<Reader>`: An iterator over the filtered lines, without line endings.
+///
+/// # Errors
+///
+/// The iterator yields an `io::Error` for lines which could not be read, e.g. because of invalid UTF-8.
+pub fn filter_iter<Reader>(
+    reader: Reader,
+    included_lines: Vec<Regex>,
+    excluded_lines: Vec<Regex>,
+) -> filter_iter::FilterIter<Reader>
+where
+    Reader: Read,
+{
+    filter_iter::FilterIter::new(reader, included_lines, excluded_lines)
 }
 
 /// Filters data from an input source based on regular expressions.
@@ -119,7 +603,7 @@ where
     Reader: Read,
 {
     let mut output = vec![];
-    filter_iter::FilterIter::new(reader, included_lines, excluded_lines).try_for_each(
+    filter_iter(reader, included_lines, excluded_lines).try_for_each(
         |filtered_line| -> Result<(), DataTableError> {
             if let Ok(line) = filtered_line {
                 output.push(line);
@@ -131,30 +615,470 @@ where
     Ok(output)
 }
 
-fn get_number<T>(line: &str, rgx: &Regex, group: bool) -> T
+/// Filters data from an input source based on regular expressions, keeping the position of each line.
+///
+/// Like [`filter`], but returns [`FilteredLine`](filter_iter::FilteredLine)s holding the line number,
+/// the index of the first matching include expression and the byte range of its match, e.g. to link back
+/// into the source file.
+///
+/// # Parameters
+///
+/// - `reader`: An input source implementing the `Read` trait.
+/// - `included_lines`: A vector of regular expressions (`Regex`) for line inclusion criteria.
+/// - `excluded_lines`: A vector of regular expressions (`Regex`) for line exclusion criteria.
+///
+/// # Type Parameters
+///
+/// - `Reader`: A type implementing the `Read` trait for the input data source.
+///
+/// # Returns
+///
+/// - `Result<Vec<FilteredLine>, ExtractionError>`: A `Result` containing the filtered lines with their positions
+///   or an `ExtractionError` in case of an error during the extraction and processing.
+///
+/// # Errors
+///
+/// This function may return an `ExtractionError` in case of errors during data extraction or table construction.
+pub fn filter_lines<Reader>(
+    reader: Reader,
+    included_lines: Vec<Regex>,
+    excluded_lines: Vec<Regex>,
+) -> Result<Vec<filter_iter::FilteredLine>, ExtractionError>
 where
-    T: num::Float + std::str::FromStr,
+    Reader: Read,
 {
+    Ok(filter_iter(reader, included_lines, excluded_lines)
+        .with_positions()
+        .flatten()
+        .collect())
+}
+
+/// Asynchronously extracts and processes data from an input source based on regular expressions.
+///
+/// This is the asynchronous counterpart of [`extract_data`], reading from a `tokio::io::AsyncRead`
+/// like a TCP stream or a serial port.
+///
+/// # Parameters
+///
+/// - `reader`: An input source implementing the `AsyncRead` trait.
+/// - `data_regex`: A vector of named regular expressions (`NamedRegex`) for data extraction.
+/// - `included_lines`: A vector of regular expressions (`Regex`) for line inclusion criteria.
+/// - `excluded_lines`: A vector of regular expressions (`Regex`) for line exclusion criteria.
+/// - `base_data_name`: An optional string reference (`Option<&str>`) for setting a base name for extracted data.
+/// - `group`: A boolean flag indicating whether the data are contained in the first group of each regular expression.
+///
+/// # Returns
+///
+/// - `Result<DataTable<T>, ExtractionError>`: A `Result` containing the constructed data table
+///   or an `ExtractionError` in case of an error during the extraction and processing.
+///
+/// # Errors
+///
+/// This function may return an `ExtractionError` in case of errors during data extraction or table construction.
+#[cfg(feature = "async")]
+pub async fn extract_data_async<Reader, T>(
+    reader: Reader,
+    data_regex: Vec<NamedRegex>,
+    included_lines: Vec<Regex>,
+    excluded_lines: Vec<Regex>,
+    base_data_name: Option<&str>,
+    group: bool,
+) -> Result<DataTable<T>, ExtractionError>
+where
+    Reader: tokio::io::AsyncRead + Unpin,
+    T: Numeric,
+{
+    Extractor {
+        data_regex,
+        included_lines,
+        excluded_lines,
+        base_data_name: base_data_name.map(str::to_string),
+        group,
+        ..Extractor::default()
+    }
+    .run_async(reader)
+    .await
+}
+
+/// Lazily and asynchronously filters lines from an input source based on regular expressions.
+///
+/// This is the asynchronous counterpart of [`filter_iter`], reading from a `tokio::io::AsyncRead`.
+/// The returned [`AsyncFilterIter`](async_filter_iter::AsyncFilterIter) offers the same options
+/// as [`FilterIter`](filter_iter::FilterIter), its lines are awaited one by one with `next`.
+///
+/// # Errors
+///
+/// The iterator yields an `io::Error` for lines which could not be read, e.g. because of invalid UTF-8.
+#[cfg(feature = "async")]
+pub fn filter_iter_async<Reader>(
+    reader: Reader,
+    included_lines: Vec<Regex>,
+    excluded_lines: Vec<Regex>,
+) -> async_filter_iter::AsyncFilterIter<Reader>
+where
+    Reader: tokio::io::AsyncRead + Unpin,
+{
+    async_filter_iter::AsyncFilterIter::new(reader, included_lines, excluded_lines)
+}
+
+/// Asynchronously filters data from an input source based on regular expressions.
+///
+/// This is the asynchronous counterpart of [`filter`], reading from a `tokio::io::AsyncRead`.
+///
+/// # Errors
+///
+/// Lines which cannot be read are skipped, so this function currently always succeeds.
+#[cfg(feature = "async")]
+pub async fn filter_async<Reader>(
+    reader: Reader,
+    included_lines: Vec<Regex>,
+    excluded_lines: Vec<Regex>,
+) -> Result<Vec<String>, ExtractionError>
+where
+    Reader: tokio::io::AsyncRead + Unpin,
+{
+    let mut output = vec![];
+    let mut lines = filter_iter_async(reader, included_lines, excluded_lines);
+    while let Some(line) = lines.next().await {
+        if let Ok(line) = line {
+            output.push(line);
+        }
+    }
+    Ok(output)
+}
+
+/// The values of a single row before they are handed out, numeric and text columns separately.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RowData<V> {
+    pub values: Vec<V>,
+    pub texts: Vec<String>,
+}
+
+/// Returns the matched text of every column populated by `rgx`, one entry per used match.
+fn get_matches<'t>(line: &'t str, rgx: &NamedRegex, group: bool) -> Vec<Vec<Option<&'t str>>> {
+    let captures: Vec<Captures> = match (&rgx.capture, &rgx.repeat) {
+        (Capture::Logfmt(_), _) | (_, Repeat::NewRow | Repeat::Aggregate(_)) => {
+            rgx.regex.captures_iter(line).flatten().collect()
+        }
+        (_, Repeat::First) => rgx
+            .regex
+            .captures(line)
+            .ok()
+            .flatten()
+            .into_iter()
+            .collect(),
+    };
+
     let match_index = if group { 1 } else { 0 };
-    if let Ok(Some(r)) = rgx.captures(line) {
-        let a = if let Some(d) = r.get(match_index) {
-            match d.as_str().parse() {
-                std::result::Result::Ok(n) => n,
-                Err(_) => T::nan(),
-            }
-        } else {
-            T::nan()
-        };
-        return a;
+    let texts = |select: &dyn Fn(&Captures<'t>) -> Option<&'t str>| {
+        captures.iter().map(select).collect::<Vec<_>>()
+    };
+    match &rgx.capture {
+        Capture::Positional => vec![texts(&|c| c.get(match_index).map(|m| m.as_str()))],
+        Capture::Named(n) => vec![texts(&|c| c.name(n).map(|m| m.as_str()))],
+        Capture::AllNamed => rgx
+            .regex
+            .capture_names()
+            .flatten()
+            .map(|n| texts(&|c| c.name(n).map(|m| m.as_str())))
+            .collect(),
+        Capture::Logfmt(keys) => keys
+            .iter()
+            .map(|key| {
+                let values = captures
+                    .iter()
+                    .filter_map(logfmt::pair)
+                    .filter(|(k, _)| k == key)
+                    .map(|(_, v)| Some(v));
+                match rgx.repeat {
+                    Repeat::First => values.take(1).collect(),
+                    _ => values.collect(),
+                }
+            })
+            .collect(),
+        #[cfg(feature = "json")]
+        Capture::JsonPath(path) => {
+            vec![texts(&|_| json::select(line, path))]
+        }
     }
-    T::nan()
 }
 
-fn get_numbers<T>(line: &str, rgxs: &[NamedRegex], group: bool) -> Vec<(String, T)>
+/// Returns the values of every numeric column populated by `rgx`, one entry per used match.
+pub(crate) fn get_values<T>(line: &str, rgx: &NamedRegex, group: bool) -> Vec<Vec<Option<T>>>
+where
+    T: Numeric,
+{
+    parse_values(get_matches(line, rgx, group), rgx, &mut Vec::new())
+}
+
+/// Parses the matched texts, adding the column names and texts which could not be parsed to `unparsed`.
+fn parse_values<T>(
+    matches: Vec<Vec<Option<&str>>>,
+    rgx: &NamedRegex,
+    unparsed: &mut Vec<(String, String)>,
+) -> Vec<Vec<Option<T>>>
+where
+    T: Numeric,
+{
+    let names = rgx.column_names();
+    matches
+        .into_iter()
+        .zip(names)
+        .map(|(matches, name)| {
+            let values = matches
+                .into_iter()
+                .map(|m| {
+                    let text = m?;
+                    let value = parse_value(text, &rgx.format, rgx.number_format.as_ref());
+                    if value.is_none() {
+                        unparsed.push((name.clone(), text.to_string()));
+                    }
+                    value
+                })
+                .map(|v| match &rgx.transform {
+                    Some(transform) => v.and_then(|v| transform.apply(v)),
+                    None => v,
+                });
+            match rgx.repeat {
+                Repeat::First | Repeat::NewRow => {
+                    let values: Vec<_> = values.collect();
+                    if values.is_empty() {
+                        vec![None]
+                    } else {
+                        values
+                    }
+                }
+                Repeat::Aggregate(aggregation) => vec![aggregation.apply(values.flatten())],
+            }
+        })
+        .collect()
+}
+
+/// Returns the texts of every text column populated by `rgx`, one entry per used match.
+///
+/// Aggregated text columns use the last match for `Aggregation::Last` and the first one otherwise.
+pub(crate) fn get_texts(line: &str, rgx: &NamedRegex, group: bool) -> Vec<Vec<Option<String>>> {
+    collect_texts(get_matches(line, rgx, group), rgx)
+}
+
+fn collect_texts(matches: Vec<Vec<Option<&str>>>, rgx: &NamedRegex) -> Vec<Vec<Option<String>>> {
+    matches
+        .into_iter()
+        .map(|matches| {
+            let mut texts: Vec<_> = matches.into_iter().map(|m| m.map(str::to_string)).collect();
+            match rgx.repeat {
+                Repeat::Aggregate(Aggregation::Last) => texts.pop().into_iter().collect(),
+                Repeat::First | Repeat::Aggregate(_) => texts.into_iter().take(1).collect(),
+                Repeat::NewRow => texts,
+            }
+        })
+        .map(|texts| if texts.is_empty() { vec![None] } else { texts })
+        .collect()
+}
+
+/// Returns the rows extracted from `line`, each containing one value per column, and adds the regexes
+/// which matched and the texts which could not be parsed to `issues`.
+///
+/// `prefilter` has to contain the regexes of `rgxs`; regexes which cannot match the line are not evaluated.
+pub(crate) fn get_rows<T>(
+    line: &str,
+    rgxs: &[NamedRegex],
+    group: bool,
+    prefilter: &Prefilter,
+    issues: &mut LineIssues,
+) -> Vec<RowData<Option<T>>>
 where
-    T: num::Float + std::str::FromStr,
+    T: Numeric,
 {
-    rgxs.iter()
-        .map(|rgx| (rgx.name.clone(), get_number(line, &rgx.regex, group)))
+    let mut values: Vec<(Vec<Option<T>>, bool)> = vec![];
+    let mut texts: Vec<(Vec<Option<String>>, bool)> = vec![];
+    for (index, (rgx, candidate)) in rgxs.iter().zip(prefilter.candidates(line)).enumerate() {
+        let repeated = rgx.repeat == Repeat::NewRow;
+        let matches = if candidate {
+            get_matches(line, rgx, group)
+        } else {
+            vec![vec![]; rgx.column_count()]
+        };
+        if matches.iter().flatten().any(Option::is_some) {
+            issues.matched.push(index);
+        }
+        if rgx.is_text() {
+            texts.extend(
+                collect_texts(matches, rgx)
+                    .into_iter()
+                    .map(|t| (t, repeated)),
+            );
+        } else {
+            values.extend(
+                parse_values(matches, rgx, &mut issues.unparsed)
+                    .into_iter()
+                    .map(|v| (v, repeated)),
+            );
+        }
+    }
+
+    let row_count = values
+        .iter()
+        .map(|c| c.0.len())
+        .chain(texts.iter().map(|c| c.0.len()))
+        .max()
+        .unwrap_or(1);
+    (0..row_count)
+        .map(|i| RowData {
+            values: values
+                .iter()
+                .map(|(v, repeated)| pick(v, i, *repeated))
+                .collect(),
+            texts: texts
+                .iter()
+                .map(|(t, repeated)| pick(t, i, *repeated).unwrap_or_default())
+                .collect(),
+        })
         .collect()
 }
+
+/// Returns the value of row `i`, repeating the first value for columns which are not `repeated`.
+fn pick<V: Clone>(values: &[Option<V>], i: usize, repeated: bool) -> Option<V> {
+    values.get(if repeated { i } else { 0 }).cloned().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "X:1 Y:2\nX:3 Y:4 X:5 Y:6\n";
+
+    fn column(table: &DataTable<f64>, name: &str) -> Vec<f64> {
+        table.col_slice_by_name(name).unwrap().to_vec()
+    }
+
+    fn extract(regexes: Vec<NamedRegex>) -> DataTable<f64> {
+        extract_data(LOG.as_bytes(), regexes, vec![], vec![], None, true).unwrap()
+    }
+
+    #[test]
+    fn named_groups_are_used_without_group_mode() {
+        let y = NamedRegex::new_with_capture(None, r"Y:(?P<y>\d+)", None).unwrap();
+        assert_eq!(y.name, "y");
+        let x =
+            NamedRegex::new_with_capture(Some("first"), r"(?P<a>X):(?P<b>\d+)", Some("b")).unwrap();
+        let table = extract(vec![x, y]);
+        assert_eq!(column(&table, "first"), [1.0, 3.0]);
+        assert_eq!(column(&table, "y"), [2.0, 4.0]);
+        assert!(NamedRegex::new_with_capture(None, r"Y:(\d+)", None).is_none());
+    }
+
+    #[test]
+    fn repeated_matches_make_new_rows_or_are_aggregated() {
+        let x = NamedRegex::new_from_string("x", r"X:(\d+)")
+            .unwrap()
+            .with_repeat(Repeat::NewRow);
+        let y = NamedRegex::new_from_string("y", r"Y:(\d+)").unwrap();
+        let table = extract(vec![x, y]);
+        assert_eq!(column(&table, "x"), [1.0, 3.0, 5.0]);
+        assert_eq!(column(&table, "y"), [2.0, 4.0, 4.0]);
+
+        let x = NamedRegex::new_from_string("x", r"X:(\d+)")
+            .unwrap()
+            .with_repeat(Repeat::Aggregate(Aggregation::Sum));
+        assert_eq!(column(&extract(vec![x]), "x"), [1.0, 8.0]);
+    }
+
+    #[test]
+    fn timestamps_are_seconds_since_the_origin() {
+        let log = "2024-01-01 00:00:01.5 up\n2024-01-01 00:01:00 up\n";
+        let timestamp = |origin| {
+            NamedRegex::new_from_string("time", r"^\S+ \S+")
+                .unwrap()
+                .with_timestamp("%Y-%m-%d %H:%M:%S%.f", origin)
+        };
+        let run = |regex| {
+            extract_data::<_, f64>(log.as_bytes(), vec![regex], vec![], vec![], None, false)
+                .unwrap()
+        };
+        let epoch = run(timestamp(TimeOrigin::Epoch));
+        assert_eq!(column(&epoch, "time"), [1704067201.5, 1704067260.0]);
+        let first = run(timestamp(TimeOrigin::FirstTimestamp));
+        assert_eq!(column(&first, "time"), [0.0, 58.5]);
+        let time = NamedRegex::new_from_string("time", r"\d+:\d+:\d+")
+            .unwrap()
+            .with_timestamp("%H:%M:%S", TimeOrigin::Epoch);
+        assert_eq!(column(&run(time), "time"), [1.0, 60.0]);
+    }
+
+    #[test]
+    fn booleans_and_texts_are_extracted_next_to_numbers() {
+        let log = "state=on level=INFO\nstate=off\n";
+        let state = NamedRegex::new_from_string("state", r"state=(\w+)")
+            .unwrap()
+            .with_format(ValueFormat::Bool);
+        let level = NamedRegex::new_from_string("level", r"level=(\w+)")
+            .unwrap()
+            .as_text();
+        let table: DataTable<f64> = extract_data(
+            log.as_bytes(),
+            vec![state, level],
+            vec![],
+            vec![],
+            None,
+            true,
+        )
+        .unwrap();
+        assert_eq!(column(&table, "state"), [1.0, 0.0]);
+        let levels: Vec<_> = table.get_text_col_by_name("level").unwrap().collect();
+        assert_eq!(levels, ["INFO", ""]);
+        assert!(table.col_slice_by_name("level").is_err());
+    }
+
+    #[test]
+    fn integer_rows_without_value_are_skipped() {
+        let log = "n=1 big=9007199254740993\nidle\nn=0x1f big=2\n";
+        let regexes = vec![
+            NamedRegex::new_from_string("n", r"n=(\w+)").unwrap(),
+            NamedRegex::new_from_string("big", r"big=(\d+)").unwrap(),
+        ];
+        let table: DataTable<i64> =
+            extract_data(log.as_bytes(), regexes, vec![], vec![], None, true).unwrap();
+        assert_eq!(table.col_slice_by_name("n").unwrap(), &[1, 31]);
+        assert_eq!(
+            table.col_slice_by_name("big").unwrap(),
+            &[9007199254740993, 2]
+        );
+    }
+
+    #[test]
+    fn transforms_are_applied_after_parsing() {
+        let scaled = NamedRegex::new_from_string("x", r"X:(\d+)")
+            .unwrap()
+            .with_scale(1.8, 32.0);
+        let custom = NamedRegex::new_from_string("y", r"Y:(\d+)")
+            .unwrap()
+            .with_scale(2.0, 0.0)
+            .with_transform(|v| v * v);
+        let table = extract(vec![scaled, custom]);
+        assert_eq!(column(&table, "x"), [33.8, 37.4]);
+        assert_eq!(column(&table, "y"), [4.0, 16.0]);
+    }
+
+    #[test]
+    fn invalid_expressions_report_the_compilation_error() {
+        let error = NamedRegex::try_new("temp", r"temp=(\d+").unwrap_err();
+        assert_eq!(error.name, "temp");
+        assert_eq!(
+            error.to_string(),
+            r"invalid regular expression 'temp=(\d+' for 'temp'"
+        );
+        assert!(std::error::Error::source(&error).is_some());
+        assert!(NamedRegex::new_from_string("temp", r"temp=(\d+").is_none());
+    }
+
+    #[test]
+    fn named_groups_populate_one_column_each() {
+        let regex = NamedRegex::new_multi_capture("xy", r"X:(?P<x>\d+) Y:(?P<y>\d+)").unwrap();
+        assert_eq!(regex.column_names(), ["x", "y"]);
+        let table = extract(vec![regex]);
+        assert_eq!(column(&table, "x"), [1.0, 3.0]);
+        assert_eq!(column(&table, "y"), [2.0, 4.0]);
+        assert!(NamedRegex::new_multi_capture("xy", r"X:(\d+)").is_none());
+    }
+}
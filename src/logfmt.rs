@@ -0,0 +1,74 @@
+use std::io::{self, BufRead, BufReader, Read};
+
+use fancy_regex::{Captures, Regex};
+
+/// Matches one `key=value` pair, the value optionally quoted like `msg="heating up"`.
+pub(crate) const PAIR: &str =
+    r#"(?:^|\s)(?P<key>[^\s="]+)=(?:"(?P<quoted>(?:[^"\\]|\\.)*)"|(?P<value>\S*))"#;
+
+/// Returns the key and the value of a pair matched by `PAIR`. Quoted values are returned without quotes.
+pub(crate) fn pair<'t>(captures: &Captures<'t>) -> Option<(&'t str, &'t str)> {
+    let key = captures.name("key")?.as_str();
+    let value = captures
+        .name("quoted")
+        .or_else(|| captures.name("value"))?
+        .as_str();
+    Some((key, value))
+}
+
+/// Collects the keys of all `key=value` pairs in the first `max_lines` lines of `reader`
+/// (all lines if `None`), in order of their first occurrence.
+///
+/// The keys can be used to create a `NamedRegex::new_logfmt` for inputs with unknown fields.
+pub fn keys<Reader: Read>(reader: Reader, max_lines: Option<usize>) -> io::Result<Vec<String>> {
+    let regex = Regex::new(PAIR).expect("The pair expression is valid.");
+    let mut keys = Vec::<String>::new();
+    let lines = BufReader::new(reader)
+        .lines()
+        .take(max_lines.unwrap_or(usize::MAX));
+    for line in lines {
+        let line = line?;
+        for key in regex
+            .captures_iter(&line)
+            .flatten()
+            .filter_map(|c| Some(pair(&c)?.0))
+        {
+            if !keys.iter().any(|k| k == key) {
+                keys.push(key.into());
+            }
+        }
+    }
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{extract_data, NamedRegex};
+
+    const LOG: &str = "temp=200.1 msg=\"heating up\"\nmsg=\"a=b\" target=210 temp=201\n";
+
+    #[test]
+    fn keys_are_collected_in_order_of_appearance() {
+        assert_eq!(
+            keys(LOG.as_bytes(), None).unwrap(),
+            ["temp", "msg", "target"]
+        );
+        assert_eq!(keys(LOG.as_bytes(), Some(1)).unwrap(), ["temp", "msg"]);
+    }
+
+    #[test]
+    fn quoted_values_do_not_start_pairs() {
+        let regex = NamedRegex::new_logfmt(&["temp", "target", "a"]);
+        let table =
+            extract_data::<_, f64>(LOG.as_bytes(), vec![regex], vec![], vec![], None, false)
+                .unwrap();
+        assert_eq!(table.col_slice_by_name("temp").unwrap(), &[200.1, 201.0]);
+        assert!(table.col_slice_by_name("target").unwrap()[0].is_nan());
+        assert!(table
+            .col_slice_by_name("a")
+            .unwrap()
+            .iter()
+            .all(|v| v.is_nan()));
+    }
+}
@@ -0,0 +1,44 @@
+/// Numeric types values can be extracted into.
+///
+/// Missing values are represented by NaN for floating point types. Integer types have no such
+/// representation, rows with missing integer values are handled by the extraction instead.
+pub trait Numeric:
+    Copy + num::Num + num::NumCast + PartialOrd + std::str::FromStr + Send + Sync
+{
+    /// Returns the value representing a missing value, if the type has one.
+    fn missing() -> Option<Self>;
+
+    /// Returns `true` if the value represents a missing value.
+    fn is_missing(&self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {$(
+        impl Numeric for $t {
+            fn missing() -> Option<Self> {
+                Some(<$t>::NAN)
+            }
+
+            fn is_missing(&self) -> bool {
+                self.is_nan()
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {$(
+        impl Numeric for $t {
+            fn missing() -> Option<Self> {
+                None
+            }
+
+            fn is_missing(&self) -> bool {
+                false
+            }
+        }
+    )*};
+}
+
+impl_float!(f32, f64);
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use fancy_regex::Regex;
+use regex::RegexSet;
+
+/// Tests a line against many regexes in a single pass with a `regex::RegexSet`.
+///
+/// Regexes using features the set does not support, like look-around or backreferences,
+/// are matched individually.
+#[derive(Debug, Clone)]
+pub(crate) struct Prefilter {
+    regexes: Vec<Regex>,
+    set: RegexSet,
+    /// The index of each regex in `set`, `None` if it is not supported.
+    slots: Vec<Option<usize>>,
+}
+
+impl Prefilter {
+    pub fn new<'a>(regexes: impl IntoIterator<Item = &'a Regex>) -> Self {
+        let regexes: Vec<Regex> = regexes.into_iter().cloned().collect();
+        let mut patterns = vec![];
+        let slots = regexes
+            .iter()
+            .map(|rgx| {
+                regex::Regex::new(rgx.as_str()).ok()?;
+                patterns.push(rgx.as_str());
+                Some(patterns.len() - 1)
+            })
+            .collect();
+        match RegexSet::new(patterns) {
+            Ok(set) => Self {
+                regexes,
+                set,
+                slots,
+            },
+            Err(_) => Self {
+                slots: vec![None; regexes.len()],
+                regexes,
+                set: RegexSet::empty(),
+            },
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regexes.is_empty()
+    }
+
+    /// Returns for each regex whether it may match `line`. Unsupported regexes always may match.
+    pub fn candidates(&self, line: &str) -> Vec<bool> {
+        let matches = self.set.matches(line);
+        self.slots
+            .iter()
+            .map(|slot| slot.is_none_or(|i| matches.matched(i)))
+            .collect()
+    }
+
+    /// Returns the index of the first regex matching `line` and the byte range of its match.
+    pub fn first_match(&self, line: &str) -> Option<(usize, Range<usize>)> {
+        let candidates = self.candidates(line);
+        self.regexes
+            .iter()
+            .zip(candidates)
+            .enumerate()
+            .filter(|(_, (_, candidate))| *candidate)
+            .find_map(|(i, (rgx, _))| Some((i, rgx.find(line).ok()??.range())))
+    }
+
+    /// Returns the text of `group` of every match of the first regex matching `line`,
+    /// where group 0 is the whole match.
+    pub fn fragments(&self, line: &str, group: usize) -> Vec<String> {
+        let Some((i, _)) = self.first_match(line) else {
+            return vec![];
+        };
+        self.regexes[i]
+            .captures_iter(line)
+            .filter_map(|captures| Some(captures.ok()?.get(group)?.as_str().to_string()))
+            .collect()
+    }
+
+    /// Returns `true` if any of the regexes matches `line`.
+    pub fn is_match(&self, line: &str) -> bool {
+        let matches = self.set.matches(line);
+        self.regexes
+            .iter()
+            .zip(&self.slots)
+            .any(|(rgx, slot)| match slot {
+                Some(i) => matches.matched(*i),
+                None => matches!(rgx.is_match(line), Ok(true)),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_regexes_are_matched_individually() {
+        let regexes = [
+            Regex::new(r"temp=\d+").unwrap(),
+            Regex::new(r"(?<=load=)\d+").unwrap(),
+        ];
+        let prefilter = Prefilter::new(&regexes);
+        assert_eq!(prefilter.candidates("idle"), [false, true]);
+        assert_eq!(prefilter.first_match("load=3 temp=2"), Some((0, 7..13)));
+        assert_eq!(prefilter.first_match("load=3"), Some((1, 5..6)));
+        assert_eq!(
+            prefilter.fragments("temp=1 temp=2", 0),
+            ["temp=1", "temp=2"]
+        );
+        assert!(prefilter.is_match("load=3"));
+        assert!(!prefilter.is_match("load=x"));
+    }
+}
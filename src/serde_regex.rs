@@ -0,0 +1,96 @@
+use fancy_regex::Regex;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+/// Serializes a regex as its pattern.
+pub(crate) fn serialize<S: Serializer>(regex: &Regex, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(regex.as_str())
+}
+
+/// Deserializes a regex from its pattern, failing if the pattern is invalid.
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern).map_err(D::Error::custom)
+}
+
+/// (De)serializes a list of regexes as their patterns.
+pub(crate) mod vec {
+    use fancy_regex::Regex;
+    use serde::{de::Error, ser::SerializeSeq, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        regexes: &[Regex],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(regexes.len()))?;
+        for regex in regexes {
+            seq.serialize_element(regex.as_str())?;
+        }
+        seq.end()
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Regex>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|pattern| Regex::new(pattern).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+/// (De)serializes an optional regex as its pattern.
+pub(crate) mod option {
+    use fancy_regex::Regex;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        regex: &Option<Regex>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match regex {
+            Some(regex) => serializer.serialize_some(regex.as_str()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Regex>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|pattern| Regex::new(&pattern).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use crate::{datatable::DataTable, format::ValueFormat, NamedRegex};
+
+    #[test]
+    fn named_regexes_are_read_with_defaults() {
+        let regex: NamedRegex =
+            serde_json::from_str(r#"{"name": "temp", "regex": "temp=(\\d+)"}"#).unwrap();
+        assert_eq!(regex.regex.as_str(), r"temp=(\d+)");
+        assert_eq!(regex.format, ValueFormat::Number);
+        let json = serde_json::to_string(&regex).unwrap();
+        assert!(json.contains(r#""regex":"temp=(\\d+)""#));
+
+        let invalid = serde_json::from_str::<NamedRegex>(r#"{"name": "t", "regex": "("}"#);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn tables_survive_a_round_trip() {
+        let table = DataTable::<f64>::from_columns(
+            vec![
+                ("time".into(), vec![1.0, 2.0]),
+                ("temp".into(), vec![20.5, 21.0]),
+            ],
+            Some("time"),
+        )
+        .unwrap();
+        let json = serde_json::to_string(&table).unwrap();
+        let back: DataTable<f64> = serde_json::from_str(&json).unwrap();
+        assert!(back.approx_eq(&table, 0.0));
+    }
+}
@@ -0,0 +1,400 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+const LOG: &str = "time=1 temp=20.5 level=INFO\n\
+                   time=2 temp=21.0 level=WARN\n\
+                   time=3 temp=22.5 level=INFO\n";
+
+/// Runs the binary with `args`, writing `stdin` to its input.
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_regextractor"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+/// Writes `content` into the file `name` in the temp directory, unique per test run.
+fn temp_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("regextractor-{}-{}", std::process::id(), name));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn extract_data_writes_csv() {
+    let output = run(
+        &[
+            "extract-data",
+            "--expr",
+            r"time=time=(\d+)",
+            "--expr",
+            r"temp=temp=([\d.]+)",
+            "--base",
+            "time",
+            "-g",
+        ],
+        LOG,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "time;temp\n1;20.5\n2;21\n3;22.5\n");
+}
+
+#[test]
+fn output_is_appended_without_repeating_the_header() {
+    let path = temp_file("append.csv", "");
+    let path_arg = path.to_str().unwrap();
+    let args = [
+        "extract-data",
+        "--expr",
+        r"temp=temp=([\d.]+)",
+        "-g",
+        "--output",
+        path_arg,
+        "--append",
+    ];
+    for _ in 0..2 {
+        assert_eq!(run(&args, LOG).status.code(), Some(0));
+    }
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(written, "temp\n20.5\n21\n22.5\n20.5\n21\n22.5\n");
+
+    let json = run(&[&args[..], &["--format", "json"]].concat(), LOG);
+    assert_eq!(json.status.code(), Some(2));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), written);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn verbose_reports_on_stderr() {
+    let expr = ["-d", r"temp=([\d.]+)", "-g"];
+    for args in [
+        &["stats", "-v"][..],
+        &["extract-data", "-vv"],
+        &["extract-data", "--verbose"],
+    ] {
+        let output = run(&[args, &expr].concat(), LOG);
+        assert_eq!(output.status.code(), Some(0));
+        assert!(!output.stderr.is_empty(), "{:?}", args);
+    }
+    // Following would never end, the missing file shows that -v is accepted.
+    let watch = run(&["watch", "-f", "missing.log", "-v"], "");
+    assert_eq!(watch.status.code(), Some(3));
+    let quiet = run(&["extract-data", "-d", r"temp=([\d.]+)", "-g"], LOG);
+    assert!(quiet.stderr.is_empty());
+}
+
+#[test]
+fn data_expr_is_used_verbatim() {
+    let output = run(&["extract-data", "-d", r"temp=([\d.]+)", "-g"], LOG);
+    assert_eq!(stdout(&output), "1\n20.5\n21\n22.5\n");
+}
+
+#[test]
+fn filter_data_inverts_with_v() {
+    let output = run(&["filter-data", "-i", "WARN", "-v"], LOG);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output).lines().count(), 2);
+    assert!(!stdout(&output).contains("WARN"));
+}
+
+#[test]
+fn stats_describes_the_columns() {
+    let output = run(&["stats", "--expr", r"temp=temp=([\d.]+)", "-g"], LOG);
+    assert_eq!(output.status.code(), Some(0));
+    let row = stdout(&output)
+        .lines()
+        .find(|l| l.starts_with("temp"))
+        .unwrap();
+    let fields: Vec<_> = row.split('|').map(str::trim).collect();
+    assert_eq!(
+        fields[1..6],
+        ["3", "0", "20.5", "22.5", "21.333333333333332"]
+    );
+}
+
+#[test]
+fn check_counts_matching_lines() {
+    let output = run(
+        &[
+            "extract-data",
+            "--check",
+            "--expr",
+            "warn=WARN",
+            "-i",
+            "level",
+        ],
+        LOG,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("3 of 3 scanned lines"));
+    let row = stdout(&output)
+        .lines()
+        .find(|l| l.contains("warn"))
+        .unwrap();
+    assert!(row.ends_with(" 1"));
+}
+
+#[test]
+fn watch_lines_honors_markers_and_sampling() {
+    let path = temp_file("watch.log", "head\na\nSTART\nb\nc\nd\nSTOP\ne\n");
+    let output = run(
+        &[
+            "watch",
+            "-f",
+            path.to_str().unwrap(),
+            "--from-start",
+            "--lines",
+            "--start-expr",
+            "START",
+            "--stop-expr",
+            "STOP",
+            "--sample-every",
+            "2",
+        ],
+        "",
+    );
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "b\nd\n");
+}
+
+#[cfg(feature = "plot")]
+#[test]
+fn plot_writes_svg_and_png() {
+    for extension in ["svg", "png"] {
+        let path = temp_file(&format!("plot.{}", extension), "");
+        let output = run(
+            &[
+                "plot",
+                "--expr",
+                r"temp=temp=([\d.]+)",
+                "-g",
+                "--output",
+                path.to_str().unwrap(),
+            ],
+            LOG,
+        );
+        let size = std::fs::metadata(&path).unwrap().len();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(output.status.code(), Some(0));
+        assert!(size > 0);
+    }
+}
+
+#[test]
+fn exit_code_1_if_nothing_matched() {
+    assert_eq!(
+        run(&["filter-data", "-i", "ERROR"], LOG).status.code(),
+        Some(1)
+    );
+    assert_eq!(
+        run(
+            &["extract-data", "-d", r"temp=([\d.]+)", "-i", "ERROR"],
+            LOG
+        )
+        .status
+        .code(),
+        Some(1)
+    );
+    // Lines without a match still produce rows of missing values, which don't count.
+    let output = run(&["extract-data", "-d", r"X:(\d+)", "-g"], LOG);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "1\nNaN\nNaN\nNaN\n");
+}
+
+#[test]
+fn exit_code_2_for_invalid_input() {
+    assert_eq!(
+        run(&["extract-data", "-d", "("], LOG).status.code(),
+        Some(2)
+    );
+    assert_eq!(
+        run(&["extract-data", "--expr", "no-name"], LOG)
+            .status
+            .code(),
+        Some(2)
+    );
+    let output = run(
+        &[
+            "extract-data",
+            "-d",
+            r"temp=([\d.]+)",
+            "-d",
+            "ERROR",
+            "--require-matches",
+        ],
+        LOG,
+    );
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn exit_code_3_for_io_errors() {
+    let missing = run(
+        &["extract-data", "-f", "/nonexistent/file.log", "-d", "x"],
+        "",
+    );
+    assert_eq!(missing.status.code(), Some(3));
+
+    let path = temp_file("invalid.log", "");
+    std::fs::write(&path, b"ok 1\n\xff\nok 2\n").unwrap();
+    let output = run(&["filter-data", "-f", path.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "ok 1\n");
+    let output = run(
+        &[
+            "extract-data",
+            "-f",
+            path.to_str().unwrap(),
+            "-d",
+            r"ok (\d)",
+            "-g",
+        ],
+        "",
+    );
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("could not read line 2"));
+}
+
+#[test]
+fn dash_reads_stdin() {
+    let args = ["extract-data", "-d", r"temp=([\d.]+)", "-g"];
+    let implicit = run(&args, LOG);
+    let dash = run(&[&args[..], &["-f", "-"]].concat(), LOG);
+    assert_eq!(dash.status.code(), Some(0));
+    assert_eq!(stdout(&dash), stdout(&implicit));
+}
+
+#[test]
+fn format_selects_the_output_format() {
+    let format = |format| {
+        let args = [
+            "extract-data",
+            "--expr",
+            r"time=time=(\d+)",
+            "--expr",
+            r"temp=temp=([\d.]+)",
+            "-g",
+            "--format",
+            format,
+        ];
+        let output = run(&args, LOG);
+        assert_eq!(output.status.code(), Some(0));
+        stdout(&output).to_string()
+    };
+    assert_eq!(format("tsv"), "time\ttemp\n1\t20.5\n2\t21\n3\t22.5\n");
+    assert_eq!(
+        format("json"),
+        "[{\"time\":1,\"temp\":20.5},{\"time\":2,\"temp\":21},{\"time\":3,\"temp\":22.5}]\n"
+    );
+    assert!(format("markdown").starts_with("| time | temp |\n| ---: | ---: |\n"));
+    assert_eq!(
+        run(&["extract-data", "--format", "xml"], LOG).status.code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn delimiter_precision_and_null_value_shape_the_csv() {
+    let args = [
+        "extract-data",
+        "--expr",
+        r"temp=temp=([\d.]+)",
+        "--expr",
+        r"load=load=([\d.]+)",
+        "-g",
+        "--delimiter",
+        ",",
+        "--precision",
+        "2",
+        "--null-value",
+        "NA",
+    ];
+    let output = run(&args, LOG);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "temp,load\n20.50,NA\n21.00,NA\n22.50,NA\n");
+}
+
+#[test]
+fn base_column_comes_first() {
+    let args = [
+        "extract-data",
+        "--expr",
+        r"time=time=(\d+)",
+        "--expr",
+        r"temp=temp=([\d.]+)",
+        "-g",
+        "--base",
+        "temp",
+    ];
+    let output = run(&args, LOG);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "temp;time\n20.5;1\n21;2\n22.5;3\n");
+    let unknown = run(&[&args[..6], &["--base", "pressure"]].concat(), LOG);
+    assert_ne!(unknown.status.code(), Some(0));
+}
+
+#[test]
+fn glob_patterns_are_expanded_in_order() {
+    let a = temp_file("glob-a.log", "temp=1\n");
+    let b = temp_file("glob-b.log", "temp=2\n");
+    let pattern = a.to_str().unwrap().replace("glob-a", "glob-*");
+    let output = run(
+        &[
+            "extract-data",
+            "--expr",
+            r"temp=temp=(\d+)",
+            "-g",
+            "-f",
+            &pattern,
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("temp;source\n1;{}\n2;{}\n", a.display(), b.display())
+    );
+
+    let none = run(
+        &[
+            "extract-data",
+            "-d",
+            "temp",
+            "-f",
+            &pattern.replace("glob-", "none-"),
+        ],
+        "",
+    );
+    assert_eq!(none.status.code(), Some(3));
+    std::fs::remove_file(a).unwrap();
+    std::fs::remove_file(b).unwrap();
+}
+
+#[test]
+fn f32_loses_the_precision_of_large_values() {
+    let input = "count=16777217\n";
+    let args = ["extract-data", "-d", r"count=(\d+)", "-g"];
+    assert_eq!(stdout(&run(&args, input)), "1\n16777217\n");
+    let f32 = run(&[&args[..], &["--f32"]].concat(), input);
+    assert_eq!(f32.status.code(), Some(0));
+    assert_eq!(stdout(&f32), "1\n16777216\n");
+}